@@ -75,7 +75,7 @@ impl Classy {
     }
 
     fn help() {
-        let width = 40;
+        let width = 44;
         println!("{}", "List of available commands:");
         println!(
             "{:<width$}{}",
@@ -144,12 +144,36 @@ impl Classy {
             "Transform [id] to DFA.",
             width = width
         );
+        println!(
+            "{:<width$}{}",
+            "[expression] => canonicalize [id]",
+            "Rename the states of [id] to q0, q1, ... in breadth first order.",
+            width = width
+        );
+        println!(
+            "{:<width$}{}",
+            "[expression] => suffix_automaton [text]",
+            "Build the DFA accepting the suffixes of [text].",
+            width = width
+        );
+        println!(
+            "{:<width$}{}",
+            "[expression] => substring_automaton [text]",
+            "Build the DFA accepting the substrings of [text].",
+            width = width
+        );
         println!(
             "{:<width$}{}",
             "compute [id] [string]",
             "Checks if [id] dfa accepts [string] or not.",
             width = width
         );
+        println!(
+            "{:<width$}{}",
+            "myhill_nerode [id]",
+            "Show Myhill-Nerode classes and distinguishing suffixes of [id] dfa.",
+            width = width
+        );
         println!(
             "{:<width$}{}",
             "write [id] [file_name]",
@@ -359,6 +383,40 @@ impl Classy {
                                 }
                                 None => println!("Expected RG id after to_dfa for {}.", *id),
                             },
+                            Some(&"canonicalize") => match tokens.get(3) {
+                                Some(rhs_id) => match self.id_to_dfa.get(*rhs_id) {
+                                    Some(dfa) => {
+                                        let canonical = dfa.canonicalize();
+                                        println!("{}", canonical);
+                                        self.id_to_dfa.insert(id.to_string(), canonical);
+                                    }
+                                    None => println!("{} is not a valid DFA id.", rhs_id),
+                                },
+                                None => {
+                                    println!("Expected DFA id after canonicalize for {}.", *id)
+                                }
+                            },
+                            Some(&"suffix_automaton") => match tokens.get(3) {
+                                Some(text) => {
+                                    let dfa = DeterministicFiniteAutomata::suffix_automaton(text);
+                                    println!("{}", dfa);
+                                    self.id_to_dfa.insert(id.to_string(), dfa);
+                                }
+                                None => {
+                                    println!("Expected text after suffix_automaton for {}.", *id)
+                                }
+                            },
+                            Some(&"substring_automaton") => match tokens.get(3) {
+                                Some(text) => {
+                                    let dfa =
+                                        DeterministicFiniteAutomata::substring_automaton(text);
+                                    println!("{}", dfa);
+                                    self.id_to_dfa.insert(id.to_string(), dfa);
+                                }
+                                None => {
+                                    println!("Expected text after substring_automaton for {}.", *id)
+                                }
+                            },
                             Some(&"read") => match tokens.iter().nth(3) {
                                 Some(x) => {
                                     if *x == "dfa" {
@@ -654,6 +712,13 @@ impl Classy {
                     }
                     None => println!("Expected id after edit."),
                 },
+                "myhill_nerode" => match tokens.get(1) {
+                    Some(id) => match self.id_to_dfa.get(*id) {
+                        Some(dfa) => println!("{}", dfa.myhill_nerode_report()),
+                        None => println!("{} is not a valid DFA id.", id),
+                    },
+                    None => println!("Expected DFA id after myhill_nerode."),
+                },
                 x => {
                     if self.id_to_dfa.contains_key(&x.to_string()) {
                         match self.id_to_dfa.get(&x.to_string()) {
@@ -0,0 +1,22 @@
+use formally_cool::regular_languages::catalog;
+use std::collections::BTreeSet;
+
+fn main() {
+    let alphabet: BTreeSet<String> = ["a".to_string(), "b".to_string()].iter().cloned().collect();
+
+    let automata = catalog::exactly_n_occurrences("a", 2, &alphabet);
+
+    println!("Exactly two a:");
+    println!("{}", automata);
+
+    let automata2 = catalog::ends_with("bb", &alphabet);
+
+    println!("Ends with bb:");
+    println!("{}", automata2);
+
+    println!("Intersection:");
+    println!("{}", automata.intersection(&automata2));
+
+    println!("Binary numerals divisible by 5:");
+    println!("{}", catalog::divisible_by(5, 2).unwrap());
+}
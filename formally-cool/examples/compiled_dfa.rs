@@ -0,0 +1,47 @@
+use formally_cool::regular_languages::*;
+use std::collections::BTreeSet;
+use std::time::Instant;
+
+// rough timing of the editable and the compiled automata on the same input, run it with
+// cargo run --release --example compiled_dfa
+fn main() {
+    let alphabet: BTreeSet<String> = ["a".to_string(), "b".to_string()].iter().cloned().collect();
+
+    let automata =
+        catalog::contains("abba", &alphabet).intersection(&catalog::ends_with("b", &alphabet));
+    let compiled = automata.compile();
+
+    let text: String = (0..1_000_000u32)
+        .map(|i| if i.count_ones() % 3 == 0 { 'a' } else { 'b' })
+        .collect();
+
+    let start = Instant::now();
+    let result = automata.compute(&text);
+    let editable = start.elapsed();
+
+    let start = Instant::now();
+    let compiled_result = compiled.compute(&text);
+    let table = start.elapsed();
+
+    assert_eq!(result, compiled_result);
+
+    println!("compute over {} letters:", text.len());
+    println!("  DeterministicFiniteAutomata: {:?}", editable);
+    println!("  CompiledDeterministicFiniteAutomata: {:?}", table);
+
+    let automata = DeterministicFiniteAutomata::from_sorted_words(["ab", "abba", "bab"]).unwrap();
+
+    let start = Instant::now();
+    let matches = automata.matcher().read_from(text.as_bytes()).unwrap();
+    let streaming = start.elapsed();
+
+    let start = Instant::now();
+    let compiled_matches = automata.compile().find_all(&text, None);
+    let table = start.elapsed();
+
+    assert_eq!(matches, compiled_matches);
+
+    println!("search for {} matches:", matches.len());
+    println!("  Matcher: {:?}", streaming);
+    println!("  CompiledDeterministicFiniteAutomata: {:?}", table);
+}
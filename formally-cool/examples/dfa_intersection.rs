@@ -1,33 +1,11 @@
 use formally_cool::regular_languages::*;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 
 fn main() {
-    let mut hash = BTreeMap::new();
+    let alphabet: BTreeSet<String> = ["a".to_string(), "b".to_string()].iter().cloned().collect();
 
-    hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
-    hash.insert((String::from("q0"), String::from("b")), String::from("q0"));
-    hash.insert((String::from("q1"), String::from("a")), String::from("q2"));
-    hash.insert((String::from("q1"), String::from("b")), String::from("q1"));
-    hash.insert((String::from("q2"), String::from("a")), String::from("q3"));
-    hash.insert((String::from("q2"), String::from("b")), String::from("q2"));
-    hash.insert((String::from("q3"), String::from("a")), String::from("q3"));
-    hash.insert((String::from("q3"), String::from("b")), String::from("q3"));
-
-    let automata = DeterministicFiniteAutomata {
-        states: [
-            "q0".to_string(),
-            "q1".to_string(),
-            "q2".to_string(),
-            "q3".to_string(),
-        ]
-        .iter()
-        .cloned()
-        .collect(),
-        alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
-        transition_function: hash,
-        start_state: String::from("q0"),
-        accept_states: [String::from("q2")].iter().cloned().collect(),
-    };
+    let automata = catalog::exactly_n_occurrences("a", 2, &alphabet);
 
     println!("Automata 1:");
     println!("{}", automata);
@@ -46,7 +24,7 @@ fn main() {
             .iter()
             .cloned()
             .collect(),
-        alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+        alphabet,
         transition_function: hash,
         start_state: String::from("r0"),
         accept_states: [String::from("r2")].iter().cloned().collect(),
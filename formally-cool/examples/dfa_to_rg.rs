@@ -1,25 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 
 use formally_cool::regular_languages::*;
 
 fn main() {
-    let mut hash = BTreeMap::new();
+    let alphabet: BTreeSet<String> = ["a".to_string(), "b".to_string()].iter().cloned().collect();
 
-    hash.insert((String::from("q0"), String::from("a")), String::from("q0"));
-    hash.insert((String::from("q0"), String::from("b")), String::from("q1"));
-    hash.insert((String::from("q1"), String::from("a")), String::from("q0"));
-    hash.insert((String::from("q1"), String::from("b")), String::from("q1"));
-
-    let automata = DeterministicFiniteAutomata {
-        states: ["q0".to_string(), "q1".to_string()]
-            .iter()
-            .cloned()
-            .collect(),
-        alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
-        transition_function: hash,
-        start_state: String::from("q0"),
-        accept_states: [String::from("q0")].iter().cloned().collect(),
-    };
+    // the empty word and the words ending with a
+    let automata = catalog::ends_with("b", &alphabet).complement();
 
     println!("{}", automata);
 
@@ -1,45 +1,17 @@
 use formally_cool::regular_languages::*;
-use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 
 fn main() {
-    let mut hash = BTreeMap::new();
+    let alphabet: BTreeSet<String> = ["a".to_string(), "b".to_string()].iter().cloned().collect();
 
-    hash.insert((String::from("q0"), String::from("a")), String::from("q0"));
-    hash.insert((String::from("q0"), String::from("b")), String::from("q1"));
-    hash.insert((String::from("q1"), String::from("a")), String::from("q0"));
-    hash.insert((String::from("q1"), String::from("b")), String::from("q1"));
-
-    let automata = DeterministicFiniteAutomata {
-        states: ["q0".to_string(), "q1".to_string()]
-            .iter()
-            .cloned()
-            .collect(),
-        alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
-        transition_function: hash,
-        start_state: String::from("q0"),
-        accept_states: [String::from("q0")].iter().cloned().collect(),
-    };
+    // the empty word and the words ending with a
+    let automata = catalog::ends_with("b", &alphabet).complement();
 
     println!("Automata 1:");
     println!("{}", automata);
 
-    let mut hash = BTreeMap::new();
-
-    hash.insert((String::from("r0"), String::from("a")), String::from("r0"));
-    hash.insert((String::from("r0"), String::from("b")), String::from("r1"));
-    hash.insert((String::from("r1"), String::from("a")), String::from("r0"));
-    hash.insert((String::from("r1"), String::from("b")), String::from("r1"));
-
-    let automata2 = DeterministicFiniteAutomata {
-        states: ["r0".to_string(), "r1".to_string()]
-            .iter()
-            .cloned()
-            .collect(),
-        alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
-        transition_function: hash,
-        start_state: String::from("r1"),
-        accept_states: [String::from("r1")].iter().cloned().collect(),
-    };
+    // the empty word and the words ending with b
+    let automata2 = catalog::ends_with("a", &alphabet).complement();
 
     println!("Automata 2:");
     println!("{}", automata2);
@@ -1,2 +1,4 @@
 pub mod context_free_languages;
+pub mod omega_regular_languages;
 pub mod regular_languages;
+pub mod transducers;
@@ -0,0 +1,614 @@
+use crate::regular_languages::{fresh_state, make_cartesian_state, set_to_state};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::fmt;
+
+// accepts the infinite words with a run visiting the accept states infinitely often
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BuchiAutomaton {
+    pub states: BTreeSet<String>,
+    pub alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String), BTreeSet<String>>,
+    pub start_state: String,
+    pub accept_states: BTreeSet<String>,
+}
+
+impl fmt::Display for BuchiAutomaton {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decoration_spacing = 3;
+
+        let table_spacing = self
+            .transition_function
+            .values()
+            .map(|targets| set_to_state(targets).len())
+            .chain(self.states.iter().map(String::len))
+            .max()
+            .unwrap_or(0)
+            + 3;
+
+        write!(
+            f,
+            "{:d$}{:width$}",
+            "",
+            "g",
+            d = decoration_spacing,
+            width = table_spacing,
+        )?;
+        for letter in &self.alphabet {
+            write!(f, "{:width$}", letter, width = table_spacing)?;
+        }
+
+        for state in &self.states {
+            let mut decorations = String::from("");
+            if self.accept_states.contains(state) {
+                decorations += "*";
+            }
+            if *state == self.start_state {
+                decorations += "->";
+            }
+            write!(
+                f,
+                "\n{:>d$}{:width$}",
+                decorations,
+                state,
+                d = decoration_spacing,
+                width = table_spacing,
+            )?;
+
+            for letter in &self.alphabet {
+                write!(
+                    f,
+                    "{:width$}",
+                    match self
+                        .transition_function
+                        .get(&(state.clone(), letter.clone()))
+                    {
+                        Some(targets) => set_to_state(targets),
+                        None => String::from("-"),
+                    },
+                    width = table_spacing,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn make_product_state(left: &str, right: &str, track: usize) -> String {
+    format!("({}, {}, {})", left, right, track)
+}
+
+// tarjan's algorithm without recursion, the strongly connected component of every node
+fn components(successors: &[Vec<usize>]) -> Vec<usize> {
+    let size = successors.len();
+    let mut index: Vec<Option<usize>> = vec![None; size];
+    let mut low = vec![0; size];
+    let mut on_stack = vec![false; size];
+    let mut stack = vec![];
+    let mut component = vec![0; size];
+    let mut counter = 0;
+    let mut components = 0;
+
+    for root in 0..size {
+        if index[root].is_some() {
+            continue;
+        }
+
+        index[root] = Some(counter);
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut work = vec![(root, 0)];
+
+        while let Some((node, next)) = work.last().cloned() {
+            if let Some(target) = successors[node].get(next) {
+                work.last_mut().unwrap().1 += 1;
+                match index[*target] {
+                    Some(target_index) => {
+                        if on_stack[*target] {
+                            low[node] = low[node].min(target_index);
+                        }
+                    }
+                    None => {
+                        index[*target] = Some(counter);
+                        low[*target] = counter;
+                        counter += 1;
+                        stack.push(*target);
+                        on_stack[*target] = true;
+                        work.push((*target, 0));
+                    }
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some((parent, _)) = work.last() {
+                low[*parent] = low[*parent].min(low[node]);
+            }
+            if Some(low[node]) == index[node] {
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component[member] = components;
+                    if member == node {
+                        break;
+                    }
+                }
+                components += 1;
+            }
+        }
+    }
+
+    component
+}
+
+impl BuchiAutomaton {
+    fn successors(&self, state: &str, letter: &str) -> BTreeSet<String> {
+        match self
+            .transition_function
+            .get(&(state.to_string(), letter.to_string()))
+        {
+            Some(targets) => targets.clone(),
+            None => BTreeSet::new(),
+        }
+    }
+
+    // breadth first from the given states, a shortest non empty word leading to one of the
+    // targets and the target it leads to
+    fn shortest_word(
+        &self,
+        from: &BTreeSet<String>,
+        targets: &BTreeSet<String>,
+    ) -> Option<(String, String)> {
+        let mut parent: BTreeMap<String, (String, String)> = BTreeMap::new();
+        let mut queue: VecDeque<String> = from.iter().cloned().collect();
+
+        while let Some(state) = queue.pop_front() {
+            for letter in &self.alphabet {
+                for next in self.successors(&state, letter) {
+                    if parent.contains_key(&next) {
+                        continue;
+                    }
+                    parent.insert(next.clone(), (state.clone(), letter.clone()));
+
+                    if targets.contains(&next) {
+                        let mut word = vec![letter.clone()];
+                        let mut actual_state = state.clone();
+                        while !from.contains(&actual_state) {
+                            let (previous, letter) = parent[&actual_state].clone();
+                            word.push(letter);
+                            actual_state = previous;
+                        }
+                        word.reverse();
+                        return Some((word.concat(), next));
+                    }
+
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+
+    // whether u v v v ... is accepted: after reading u, runs over v are followed as a graph on
+    // (state, position in v), and some reachable accepting node has to lie on a cycle
+    pub fn accepts(&self, prefix: &str, period: &str) -> bool {
+        let period: Vec<String> = period.chars().map(|symbol| symbol.to_string()).collect();
+        if period.is_empty() {
+            return false;
+        }
+
+        let mut actual_states: BTreeSet<String> =
+            [self.start_state.clone()].iter().cloned().collect();
+        for symbol in prefix.chars() {
+            actual_states = actual_states
+                .iter()
+                .flat_map(|state| self.successors(state, &symbol.to_string()))
+                .collect();
+        }
+
+        let step = |(state, position): &(String, usize)| -> Vec<(String, usize)> {
+            self.successors(state, &period[*position])
+                .into_iter()
+                .map(|next| (next, (position + 1) % period.len()))
+                .collect()
+        };
+        let reach = |from: Vec<(String, usize)>| -> BTreeSet<(String, usize)> {
+            let mut reached = BTreeSet::new();
+            let mut stack = from;
+            while let Some(node) = stack.pop() {
+                if reached.insert(node.clone()) {
+                    stack.extend(step(&node));
+                }
+            }
+            reached
+        };
+
+        let reachable = reach(actual_states.into_iter().map(|state| (state, 0)).collect());
+
+        reachable
+            .iter()
+            .filter(|(state, _)| self.accept_states.contains(state))
+            .any(|node| reach(step(node)).contains(node))
+    }
+
+    // an accepted word u v v v ..., or None when the language is empty: the accept states on a
+    // cycle are those in a strongly connected component with an edge inside, u is a shortest
+    // word reaching one of them and v a shortest word coming back to it, which need not give
+    // the shortest lasso overall
+    pub fn accepting_lasso(&self) -> Option<(String, String)> {
+        let mut reachable = vec![self.start_state.clone()];
+        let mut index: BTreeMap<String, usize> = BTreeMap::new();
+        index.insert(self.start_state.clone(), 0);
+        let mut successors: Vec<Vec<usize>> = vec![];
+
+        while successors.len() < reachable.len() {
+            let state = reachable[successors.len()].clone();
+            let mut targets = vec![];
+            for letter in &self.alphabet {
+                for next in self.successors(&state, letter) {
+                    let next_index = match index.get(&next) {
+                        Some(next_index) => *next_index,
+                        None => {
+                            index.insert(next.clone(), reachable.len());
+                            reachable.push(next);
+                            reachable.len() - 1
+                        }
+                    };
+                    targets.push(next_index);
+                }
+            }
+            successors.push(targets);
+        }
+
+        let component = components(&successors);
+        let mut component_size = BTreeMap::new();
+        for member in &component {
+            *component_size.entry(*member).or_insert(0) += 1;
+        }
+
+        let on_cycle: BTreeSet<String> = (0..reachable.len())
+            .filter(|state| self.accept_states.contains(&reachable[*state]))
+            .filter(|state| {
+                component_size[&component[*state]] > 1 || successors[*state].contains(state)
+            })
+            .map(|state| reachable[state].clone())
+            .collect();
+
+        let (prefix, accept_state) = if on_cycle.contains(&self.start_state) {
+            (String::new(), self.start_state.clone())
+        } else {
+            let start: BTreeSet<String> = [self.start_state.clone()].iter().cloned().collect();
+            self.shortest_word(&start, &on_cycle)?
+        };
+
+        let cycle: BTreeSet<String> = [accept_state].iter().cloned().collect();
+        let (period, _) = self.shortest_word(&cycle, &cycle)?;
+
+        Some((prefix, period))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accepting_lasso().is_none()
+    }
+
+    pub fn rename_states(&self, rename: impl Fn(&String) -> String) -> Self {
+        BuchiAutomaton {
+            states: self.states.iter().map(&rename).collect(),
+            alphabet: self.alphabet.clone(),
+            transition_function: self
+                .transition_function
+                .iter()
+                .map(|((state, letter), targets)| {
+                    (
+                        (rename(state), letter.clone()),
+                        targets.iter().map(&rename).collect(),
+                    )
+                })
+                .collect(),
+            start_state: rename(&self.start_state),
+            accept_states: self.accept_states.iter().map(&rename).collect(),
+        }
+    }
+
+    // every state name in use, declared or not
+    fn state_names(&self) -> BTreeSet<String> {
+        let mut states = self.states.clone();
+        states.insert(self.start_state.clone());
+        states.extend(self.accept_states.iter().cloned());
+        for ((state, _), targets) in &self.transition_function {
+            states.insert(state.clone());
+            states.extend(targets.iter().cloned());
+        }
+        states
+    }
+
+    // when the state names clash every state is paired with the side it comes from; the new
+    // start state copies the moves of both start states and is never entered again
+    pub fn union(&self, other: &Self) -> Self {
+        if !self.state_names().is_disjoint(&other.state_names()) {
+            let side = |side: &'static str| {
+                move |state: &String| make_cartesian_state(state, &String::from(side))
+            };
+            return self
+                .rename_states(side("1"))
+                .union(&other.rename_states(side("2")));
+        }
+
+        let mut states: BTreeSet<String> = self.states.union(&other.states).cloned().collect();
+        let alphabet: BTreeSet<String> = self.alphabet.union(&other.alphabet).cloned().collect();
+
+        let start_state = fresh_state("q", &states);
+        states.insert(start_state.clone());
+
+        let mut transition_function = self.transition_function.clone();
+        for (key, targets) in &other.transition_function {
+            transition_function
+                .entry(key.clone())
+                .or_default()
+                .extend(targets.iter().cloned());
+        }
+
+        for letter in &alphabet {
+            let targets: BTreeSet<String> = self
+                .successors(&self.start_state, letter)
+                .union(&other.successors(&other.start_state, letter))
+                .cloned()
+                .collect();
+            if !targets.is_empty() {
+                transition_function.insert((start_state.clone(), letter.clone()), targets);
+            }
+        }
+
+        BuchiAutomaton {
+            states,
+            alphabet,
+            transition_function,
+            start_state,
+            accept_states: self
+                .accept_states
+                .union(&other.accept_states)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    // product with a track telling whose accept states are awaited: track 1 waits for self and
+    // moves to 2 on one of them, track 2 waits for other and moves back; accepting a state of
+    // self on track 1 infinitely often means both kept visiting their accept states
+    pub fn intersection(&self, other: &Self) -> Self {
+        let alphabet: BTreeSet<String> = self
+            .alphabet
+            .intersection(&other.alphabet)
+            .cloned()
+            .collect();
+
+        let mut states = BTreeSet::new();
+        let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        let mut queue = VecDeque::new();
+        queue.push_back((self.start_state.clone(), other.start_state.clone(), 1));
+
+        while let Some((left, right, track)) = queue.pop_front() {
+            let state = make_product_state(&left, &right, track);
+            if !states.insert(state.clone()) {
+                continue;
+            }
+
+            let next_track = match track {
+                1 if self.accept_states.contains(&left) => 2,
+                2 if other.accept_states.contains(&right) => 1,
+                _ => track,
+            };
+            if track == 1 && self.accept_states.contains(&left) {
+                accept_states.insert(state.clone());
+            }
+
+            for letter in &alphabet {
+                for left_next in self.successors(&left, letter) {
+                    for right_next in other.successors(&right, letter) {
+                        transition_function
+                            .entry((state.clone(), letter.clone()))
+                            .or_default()
+                            .insert(make_product_state(&left_next, &right_next, next_track));
+                        queue.push_back((left_next.clone(), right_next, next_track));
+                    }
+                }
+            }
+        }
+
+        BuchiAutomaton {
+            states,
+            alphabet,
+            transition_function,
+            start_state: make_product_state(&self.start_state, &other.start_state, 1),
+            accept_states,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_automaton(
+        start_state: &str,
+        accept_states: &[&str],
+        transitions: &[(&str, &str, &str)],
+    ) -> BuchiAutomaton {
+        let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        let mut states = BTreeSet::new();
+
+        for (state, letter, target) in transitions {
+            transition_function
+                .entry((state.to_string(), letter.to_string()))
+                .or_default()
+                .insert(target.to_string());
+            states.insert(state.to_string());
+            states.insert(target.to_string());
+        }
+
+        BuchiAutomaton {
+            states,
+            alphabet: ["a", "b"].iter().map(|x| x.to_string()).collect(),
+            transition_function,
+            start_state: start_state.to_string(),
+            accept_states: accept_states.iter().map(|x| x.to_string()).collect(),
+        }
+    }
+
+    // infinitely many of the letter, over states named after the prefix
+    fn make_automaton_infinitely_many(letter: &str, prefix: &str) -> BuchiAutomaton {
+        let other = if letter == "a" { "b" } else { "a" };
+        let waiting = String::from(prefix) + "0";
+        let seen = String::from(prefix) + "1";
+
+        make_automaton(
+            &waiting,
+            &[&seen],
+            &[
+                (&waiting, letter, &seen),
+                (&waiting, other, &waiting),
+                (&seen, letter, &seen),
+                (&seen, other, &waiting),
+            ],
+        )
+    }
+
+    // finitely many a: guess when the last one has been read
+    fn make_automaton_finitely_many_a() -> BuchiAutomaton {
+        make_automaton(
+            "p",
+            &["r"],
+            &[
+                ("p", "a", "p"),
+                ("p", "b", "p"),
+                ("p", "b", "r"),
+                ("r", "b", "r"),
+            ],
+        )
+    }
+
+    #[test]
+    fn accepts() {
+        let infinitely_many_a = make_automaton_infinitely_many("a", "i");
+        assert!(infinitely_many_a.accepts("", "ab"));
+        assert!(infinitely_many_a.accepts("bbb", "bba"));
+        assert!(!infinitely_many_a.accepts("aaa", "b"));
+        assert!(!infinitely_many_a.accepts("a", ""));
+
+        let finitely_many_a = make_automaton_finitely_many_a();
+        assert!(finitely_many_a.accepts("abab", "b"));
+        assert!(finitely_many_a.accepts("", "b"));
+        assert!(!finitely_many_a.accepts("b", "ba"));
+    }
+
+    #[test]
+    fn emptiness() {
+        let infinitely_many_a = make_automaton_infinitely_many("a", "i");
+        assert_eq!(
+            infinitely_many_a.accepting_lasso(),
+            Some((String::from("a"), String::from("a")))
+        );
+
+        let finitely_many_a = make_automaton_finitely_many_a();
+        assert_eq!(
+            finitely_many_a.accepting_lasso(),
+            Some((String::from("b"), String::from("b")))
+        );
+
+        // the accept state can be reached but not revisited
+        let once = make_automaton(
+            "p",
+            &["r"],
+            &[("p", "a", "r"), ("r", "b", "s"), ("s", "b", "s")],
+        );
+        assert!(once.is_empty());
+
+        // only the component of q and r has a cycle through the accept state
+        let through_cycle = make_automaton(
+            "p",
+            &["r", "s"],
+            &[
+                ("p", "b", "p"),
+                ("p", "a", "q"),
+                ("q", "b", "r"),
+                ("r", "a", "q"),
+                ("p", "b", "s"),
+            ],
+        );
+        assert_eq!(
+            through_cycle.accepting_lasso(),
+            Some((String::from("ab"), String::from("ab")))
+        );
+    }
+
+    #[test]
+    fn intersection() {
+        let infinitely_many_a = make_automaton_infinitely_many("a", "i");
+        let finitely_many_a = make_automaton_finitely_many_a();
+
+        assert!(infinitely_many_a.intersection(&finitely_many_a).is_empty());
+
+        let both = infinitely_many_a.intersection(&make_automaton_infinitely_many("b", "j"));
+        let (prefix, period) = both.accepting_lasso().unwrap();
+
+        assert!(period.contains('a') && period.contains('b'));
+        assert!(both.accepts(&prefix, &period));
+        assert!(both.accepts("", "aab"));
+        assert!(!both.accepts("ab", "a"));
+    }
+
+    #[test]
+    fn union() {
+        let infinitely_many_a = make_automaton_infinitely_many("a", "i");
+        let finitely_many_a = make_automaton_finitely_many_a();
+
+        let union = infinitely_many_a.union(&finitely_many_a);
+
+        for (prefix, period) in &[("", "ab"), ("aab", "b"), ("", "b"), ("b", "a")] {
+            assert!(union.accepts(prefix, period));
+        }
+
+        let infinitely_many_b = make_automaton_infinitely_many("b", "j");
+        let eventually_only_b = infinitely_many_a
+            .union(&infinitely_many_b)
+            .intersection(&finitely_many_a);
+        assert!(eventually_only_b.accepts("a", "b"));
+        assert!(!eventually_only_b.accepts("", "a"));
+        assert!(!eventually_only_b.accepts("", "ab"));
+    }
+
+    #[test]
+    fn union_clashing_names() {
+        let only_a = make_automaton("x", &["x"], &[("x", "a", "x")]);
+        let only_b = make_automaton("x", &["x"], &[("x", "b", "x")]);
+
+        let union = only_a.union(&only_b);
+        assert!(union.accepts("", "a"));
+        assert!(union.accepts("", "b"));
+        assert!(!union.accepts("", "ab"));
+        assert!(!union.accepts("a", "b"));
+
+        // the same language twice under the same names
+        let infinitely_many_a = make_automaton_infinitely_many("a", "i");
+        let twice = infinitely_many_a.union(&infinitely_many_a);
+        assert!(twice.accepts("b", "ab"));
+        assert!(!twice.accepts("a", "b"));
+    }
+
+    #[test]
+    fn display() {
+        let finitely_many_a = make_automaton_finitely_many_a();
+
+        assert_eq!(
+            finitely_many_a.to_string(),
+            "   g        a        b        \n ->p        (p)      (p, r)   \n  *r        -        (r)      "
+        );
+    }
+}
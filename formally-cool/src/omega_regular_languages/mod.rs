@@ -0,0 +1,3 @@
+mod buchi_automata;
+
+pub use self::buchi_automata::*;
@@ -0,0 +1,262 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+pub trait Teacher {
+    fn membership(&mut self, word: &str) -> bool;
+    fn equivalence(&mut self, hypothesis: &DeterministicFiniteAutomata) -> Option<String>;
+}
+
+pub struct DfaTeacher {
+    pub automata: DeterministicFiniteAutomata,
+    pub membership_queries: usize,
+    pub equivalence_queries: usize,
+}
+
+impl DfaTeacher {
+    pub fn new(automata: DeterministicFiniteAutomata) -> Self {
+        DfaTeacher {
+            automata,
+            membership_queries: 0,
+            equivalence_queries: 0,
+        }
+    }
+}
+
+impl Teacher for DfaTeacher {
+    fn membership(&mut self, word: &str) -> bool {
+        self.membership_queries += 1;
+        self.automata.compute(word)
+    }
+
+    fn equivalence(&mut self, hypothesis: &DeterministicFiniteAutomata) -> Option<String> {
+        self.equivalence_queries += 1;
+        self.automata.find_counterexample(hypothesis)
+    }
+}
+
+struct ObservationTable<'a, T: Teacher> {
+    teacher: &'a mut T,
+    answers: HashMap<String, bool>,
+    prefixes: Vec<String>,
+    suffixes: Vec<String>,
+}
+
+impl<'a, T: Teacher> ObservationTable<'a, T> {
+    fn query(&mut self, word: String) -> bool {
+        match self.answers.get(&word) {
+            Some(answer) => *answer,
+            None => {
+                let answer = self.teacher.membership(&word);
+                self.answers.insert(word, answer);
+                answer
+            }
+        }
+    }
+
+    fn row(&mut self, prefix: &str) -> Vec<bool> {
+        let suffixes = self.suffixes.clone();
+
+        suffixes
+            .iter()
+            .map(|suffix| self.query(prefix.to_string() + suffix))
+            .collect()
+    }
+
+    // returns the extension of a prefix whose row is missing from the upper table, if any
+    fn unclosed_prefix(&mut self, alphabet: &BTreeSet<String>) -> Option<String> {
+        let prefixes = self.prefixes.clone();
+        let rows: BTreeSet<Vec<bool>> = prefixes.iter().map(|prefix| self.row(prefix)).collect();
+
+        for prefix in &prefixes {
+            for letter in alphabet {
+                let extension = prefix.clone() + letter;
+                if !rows.contains(&self.row(&extension)) {
+                    return Some(extension);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn hypothesis(&mut self, alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+        let prefixes = self.prefixes.clone();
+
+        // rows of the upper table are pairwise distinct, so every prefix is one state
+        let mut row_to_state = BTreeMap::new();
+
+        for (i, prefix) in prefixes.iter().enumerate() {
+            row_to_state.insert(self.row(prefix), String::from("q") + &i.to_string());
+        }
+
+        let mut states = BTreeSet::new();
+        let mut transition_function = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        for prefix in &prefixes {
+            let state = row_to_state[&self.row(prefix)].clone();
+
+            if self.query(prefix.clone()) {
+                accept_states.insert(state.clone());
+            }
+
+            for letter in alphabet {
+                let out_state = row_to_state[&self.row(&(prefix.clone() + letter))].clone();
+                transition_function.insert((state.clone(), letter.clone()), out_state);
+            }
+
+            states.insert(state);
+        }
+
+        DeterministicFiniteAutomata {
+            states,
+            alphabet: alphabet.clone(),
+            transition_function,
+            start_state: String::from("q0"),
+            accept_states,
+        }
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    // Angluin's L*, with counterexamples handled as in Maler and Pnueli: every suffix of the
+    // counterexample becomes a column, which keeps the table consistent by construction. a true
+    // counterexample always brings a new suffix, since with all of its suffixes as columns the
+    // hypothesis already agrees with it; one that brings none comes from a wrong teacher and
+    // gives None instead of asking forever
+    pub fn learn<T: Teacher>(alphabet: &BTreeSet<String>, teacher: &mut T) -> Option<Self> {
+        let mut table = ObservationTable {
+            teacher,
+            answers: HashMap::new(),
+            prefixes: vec![String::new()],
+            suffixes: vec![String::new()],
+        };
+
+        loop {
+            while let Some(prefix) = table.unclosed_prefix(alphabet) {
+                table.prefixes.push(prefix);
+            }
+
+            let hypothesis = table.hypothesis(alphabet);
+
+            match table.teacher.equivalence(&hypothesis) {
+                Some(counterexample) => {
+                    let symbols: Vec<char> = counterexample.chars().collect();
+                    let columns = table.suffixes.len();
+
+                    for i in 0..symbols.len() {
+                        let suffix: String = symbols[i..].iter().collect();
+                        if !table.suffixes.contains(&suffix) {
+                            table.suffixes.push(suffix);
+                        }
+                    }
+
+                    if table.suffixes.len() == columns {
+                        return None;
+                    }
+                }
+                None => return Some(hypothesis),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_automata_third_from_last_is_a() -> DeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        // states remember the last three letters read
+        for state in 0..8 {
+            let name = String::from("q") + &state.to_string();
+            let shifted = (state << 1) & 7;
+
+            hash.insert(
+                (name.clone(), String::from("a")),
+                String::from("q") + &(shifted | 1).to_string(),
+            );
+            hash.insert(
+                (name, String::from("b")),
+                String::from("q") + &shifted.to_string(),
+            );
+        }
+
+        DeterministicFiniteAutomata {
+            states: (0..8).map(|i| String::from("q") + &i.to_string()).collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: (4..8).map(|i| String::from("q") + &i.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn learn() {
+        let automata = make_automata_third_from_last_is_a();
+        let mut teacher = DfaTeacher::new(automata.clone());
+
+        let learned = DeterministicFiniteAutomata::learn(&automata.alphabet, &mut teacher).unwrap();
+
+        assert_eq!(automata.find_counterexample(&learned), None);
+        assert_eq!(learned.states.len(), 8);
+        assert!(teacher.equivalence_queries >= 2);
+    }
+
+    #[test]
+    fn learn_partial_automata() {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q0"));
+
+        let automata = DeterministicFiniteAutomata {
+            states: ["q0".to_string(), "q1".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q0")].iter().cloned().collect(),
+        };
+        let mut teacher = DfaTeacher::new(automata.clone());
+
+        let learned = DeterministicFiniteAutomata::learn(&automata.alphabet, &mut teacher).unwrap();
+
+        assert_eq!(automata.find_counterexample(&learned), None);
+        assert_eq!(learned.states.len(), 3);
+    }
+
+    struct RepeatingTeacher {
+        automata: DeterministicFiniteAutomata,
+        equivalence_queries: usize,
+    }
+
+    impl Teacher for RepeatingTeacher {
+        fn membership(&mut self, word: &str) -> bool {
+            self.automata.compute(word)
+        }
+
+        // always answers the same word, which is no counterexample once it has been learned
+        fn equivalence(&mut self, _hypothesis: &DeterministicFiniteAutomata) -> Option<String> {
+            self.equivalence_queries += 1;
+            Some(String::from("aab"))
+        }
+    }
+
+    #[test]
+    fn learn_wrong_teacher() {
+        let automata = make_automata_third_from_last_is_a();
+        let mut teacher = RepeatingTeacher {
+            automata: automata.clone(),
+            equivalence_queries: 0,
+        };
+
+        assert!(DeterministicFiniteAutomata::learn(&automata.alphabet, &mut teacher).is_none());
+        assert_eq!(teacher.equivalence_queries, 2);
+    }
+}
@@ -0,0 +1,302 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordMatch {
+    pub position: usize,
+    pub keyword: String,
+}
+
+// automata holds the goto function with the failure links already folded in, so searching is
+// one transition per symbol; outputs lists the keywords recognized in each state, longest first
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AhoCorasick {
+    pub keywords: Vec<String>,
+    pub automata: DeterministicFiniteAutomata,
+    pub outputs: BTreeMap<String, Vec<String>>,
+}
+
+pub struct AhoCorasickSearch<'a> {
+    aho_corasick: &'a AhoCorasick,
+    state: String,
+    position: usize,
+}
+
+impl<'a> AhoCorasickSearch<'a> {
+    // positions count symbols from the beginning of the stream, across every chunk fed so far
+    pub fn feed(&mut self, chunk: &str) -> Vec<KeywordMatch> {
+        let mut matches = vec![];
+
+        for symbol in chunk.chars() {
+            self.position += 1;
+
+            self.state = match self
+                .aho_corasick
+                .automata
+                .transition_function
+                .get(&(self.state.clone(), symbol.to_string()))
+            {
+                Some(out_state) => out_state.clone(),
+                None => self.aho_corasick.automata.start_state.clone(),
+            };
+
+            if let Some(keywords) = self.aho_corasick.outputs.get(&self.state) {
+                for keyword in keywords {
+                    matches.push(KeywordMatch {
+                        position: self.position - keyword.chars().count(),
+                        keyword: keyword.clone(),
+                    });
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+impl AhoCorasick {
+    // None when a keyword is empty, since it would match at every position
+    pub fn new<I, S>(keywords: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut unique_keywords: Vec<String> = vec![];
+        let mut goto: Vec<BTreeMap<char, usize>> = vec![BTreeMap::new()];
+        let mut keyword_of = vec![None];
+
+        for keyword in keywords {
+            let keyword = keyword.as_ref();
+
+            if keyword.is_empty() {
+                return None;
+            }
+            if unique_keywords.iter().any(|unique| unique == keyword) {
+                continue;
+            }
+
+            let mut state = 0;
+            for symbol in keyword.chars() {
+                state = match goto[state].get(&symbol) {
+                    Some(next_state) => *next_state,
+                    None => {
+                        let next_state = goto.len();
+                        goto.push(BTreeMap::new());
+                        keyword_of.push(None);
+                        goto[state].insert(symbol, next_state);
+                        next_state
+                    }
+                };
+            }
+
+            keyword_of[state] = Some(keyword.to_string());
+            unique_keywords.push(keyword.to_string());
+        }
+
+        // breadth first, so the failure of every state is known before its children need it
+        let mut failure = vec![0; goto.len()];
+        let mut delta: Vec<BTreeMap<char, usize>> = vec![BTreeMap::new(); goto.len()];
+        let mut outputs: Vec<Vec<String>> = vec![vec![]; goto.len()];
+        let mut order = vec![];
+        let mut queue = VecDeque::new();
+
+        let symbols: BTreeSet<char> = goto
+            .iter()
+            .flat_map(|edges| edges.keys().cloned())
+            .collect();
+
+        queue.push_back(0);
+
+        while let Some(state) = queue.pop_front() {
+            order.push(state);
+
+            if let Some(keyword) = &keyword_of[state] {
+                outputs[state].push(keyword.clone());
+            }
+            if state != 0 {
+                let inherited = outputs[failure[state]].clone();
+                outputs[state].extend(inherited);
+            }
+
+            for symbol in &symbols {
+                match goto[state].get(symbol) {
+                    Some(child) => {
+                        failure[*child] = if state == 0 {
+                            0
+                        } else {
+                            delta[failure[state]][symbol]
+                        };
+                        delta[state].insert(*symbol, *child);
+                        queue.push_back(*child);
+                    }
+                    None => {
+                        let out_state = if state == 0 {
+                            0
+                        } else {
+                            delta[failure[state]][symbol]
+                        };
+                        delta[state].insert(*symbol, out_state);
+                    }
+                }
+            }
+        }
+
+        let mut names = vec![String::new(); goto.len()];
+        for (i, state) in order.iter().enumerate() {
+            names[*state] = String::from("q") + &i.to_string();
+        }
+
+        let mut transition_function = BTreeMap::new();
+        for state in &order {
+            for (symbol, out_state) in &delta[*state] {
+                transition_function.insert(
+                    (names[*state].clone(), symbol.to_string()),
+                    names[*out_state].clone(),
+                );
+            }
+        }
+
+        let automata = DeterministicFiniteAutomata {
+            states: names.iter().cloned().collect(),
+            alphabet: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            transition_function,
+            start_state: names[0].clone(),
+            accept_states: order
+                .iter()
+                .filter(|state| !outputs[**state].is_empty())
+                .map(|state| names[*state].clone())
+                .collect(),
+        };
+
+        Some(AhoCorasick {
+            keywords: unique_keywords,
+            automata,
+            outputs: order
+                .iter()
+                .filter(|state| !outputs[**state].is_empty())
+                .map(|state| (names[*state].clone(), outputs[*state].clone()))
+                .collect(),
+        })
+    }
+
+    pub fn search(&self) -> AhoCorasickSearch<'_> {
+        AhoCorasickSearch {
+            aho_corasick: self,
+            state: self.automata.start_state.clone(),
+            position: 0,
+        }
+    }
+
+    pub fn find_all(&self, text: &str) -> Vec<KeywordMatch> {
+        self.search().feed(text)
+    }
+
+    // complete automaton over the keyword letters plus the given alphabet accepting every text
+    // that contains a keyword: every accept state collapses into a single absorbing one
+    pub fn dfa(&self, alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+        let mut automata = self.automata.clone();
+
+        automata.alphabet.extend(alphabet.iter().cloned());
+
+        let found = match self.automata.accept_states.iter().next() {
+            Some(found) => found.clone(),
+            None => return automata,
+        };
+
+        for state in &self.automata.states {
+            for letter in &automata.alphabet {
+                let key = (state.clone(), letter.clone());
+                let out_state = match self.automata.transition_function.get(&key) {
+                    _ if self.automata.accept_states.contains(state) => found.clone(),
+                    Some(out_state) if self.automata.accept_states.contains(out_state) => {
+                        found.clone()
+                    }
+                    Some(out_state) => out_state.clone(),
+                    None => self.automata.start_state.clone(),
+                };
+                automata.transition_function.insert(key, out_state);
+            }
+        }
+
+        automata.accept_states = [found].iter().cloned().collect();
+
+        automata.remove_unreachable_states()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(found: &[KeywordMatch]) -> Vec<(usize, &str)> {
+        found
+            .iter()
+            .map(|found| (found.position, found.keyword.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn find_all() {
+        let aho_corasick = AhoCorasick::new(["he", "she", "his", "hers"]).unwrap();
+
+        assert_eq!(aho_corasick.automata.states.len(), 10);
+        assert_eq!(
+            matches(&aho_corasick.find_all("ushers")),
+            vec![(1, "she"), (2, "he"), (2, "hers")]
+        );
+        assert_eq!(
+            matches(&aho_corasick.find_all("a his-hershe!")),
+            vec![(2, "his"), (6, "he"), (6, "hers"), (9, "she"), (10, "he")]
+        );
+    }
+
+    #[test]
+    fn search_across_chunks() {
+        let aho_corasick = AhoCorasick::new(["error", "err", "warn"]).unwrap();
+
+        let text = "ok\nerror: disk\nwarning\nerr";
+
+        let mut search = aho_corasick.search();
+        let mut streamed = vec![];
+        for chunk in ["ok\ner", "ror: disk\nwa", "rning\ne", "rr"] {
+            streamed.extend(search.feed(chunk));
+        }
+
+        assert_eq!(streamed, aho_corasick.find_all(text));
+        assert_eq!(
+            matches(&streamed),
+            vec![(3, "err"), (3, "error"), (15, "warn"), (23, "err")]
+        );
+    }
+
+    #[test]
+    fn dfa() {
+        let aho_corasick = AhoCorasick::new(["ab", "bc"]).unwrap();
+        let alphabet = ["a", "b", "c", "d"].iter().map(|x| x.to_string()).collect();
+
+        let automata = aho_corasick.dfa(&alphabet);
+
+        assert!(automata.compute("dab"));
+        assert!(automata.compute("bcdd"));
+        assert!(automata.compute("aabcd"));
+        assert!(!automata.compute("acbdba"));
+        assert!(!automata.compute(""));
+        assert_eq!(
+            automata.myhill_nerode_report().classes.len(),
+            automata.states.len()
+        );
+
+        let yaml = serde_yaml::to_string(&aho_corasick).unwrap();
+        let parsed: AhoCorasick = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(parsed.find_all("abc"), aho_corasick.find_all("abc"));
+    }
+
+    #[test]
+    fn empty_keyword() {
+        assert!(AhoCorasick::new(["a", ""]).is_none());
+    }
+}
@@ -0,0 +1,482 @@
+use super::deterministic_finite_automata::fresh_state;
+use super::deterministic_finite_automata::make_cartesian_state;
+use super::deterministic_finite_automata::set_to_state;
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+
+// positive boolean formula over states; an empty And is true and an empty Or is false
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Formula {
+    True,
+    False,
+    State(String),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+// keeps the sets that contain no other set of the family
+fn minimal_sets(sets: BTreeSet<BTreeSet<String>>) -> BTreeSet<BTreeSet<String>> {
+    sets.iter()
+        .filter(|set| {
+            !sets
+                .iter()
+                .any(|other| other != *set && other.is_subset(set))
+        })
+        .cloned()
+        .collect()
+}
+
+impl Formula {
+    // whether the formula holds when exactly the given states are true
+    pub fn evaluate(&self, true_states: &BTreeSet<String>) -> bool {
+        match self {
+            Formula::True => true,
+            Formula::False => false,
+            Formula::State(state) => true_states.contains(state),
+            Formula::And(formulas) => formulas.iter().all(|f| f.evaluate(true_states)),
+            Formula::Or(formulas) => formulas.iter().any(|f| f.evaluate(true_states)),
+        }
+    }
+
+    pub fn dual(&self) -> Self {
+        match self {
+            Formula::True => Formula::False,
+            Formula::False => Formula::True,
+            Formula::State(state) => Formula::State(state.clone()),
+            Formula::And(formulas) => Formula::Or(formulas.iter().map(Formula::dual).collect()),
+            Formula::Or(formulas) => Formula::And(formulas.iter().map(Formula::dual).collect()),
+        }
+    }
+
+    pub fn rename_states(&self, rename: &impl Fn(&String) -> String) -> Self {
+        match self {
+            Formula::True => Formula::True,
+            Formula::False => Formula::False,
+            Formula::State(state) => Formula::State(rename(state)),
+            Formula::And(formulas) => Formula::And(
+                formulas
+                    .iter()
+                    .map(|formula| formula.rename_states(rename))
+                    .collect(),
+            ),
+            Formula::Or(formulas) => Formula::Or(
+                formulas
+                    .iter()
+                    .map(|formula| formula.rename_states(rename))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn collect_states(&self, states: &mut BTreeSet<String>) {
+        match self {
+            Formula::True | Formula::False => (),
+            Formula::State(state) => {
+                states.insert(state.clone());
+            }
+            Formula::And(formulas) | Formula::Or(formulas) => {
+                for formula in formulas {
+                    formula.collect_states(states);
+                }
+            }
+        }
+    }
+
+    // the minimal sets of states making the formula true, that is its disjunctive normal form
+    pub fn minimal_models(&self) -> BTreeSet<BTreeSet<String>> {
+        match self {
+            Formula::True => [BTreeSet::new()].iter().cloned().collect(),
+            Formula::False => BTreeSet::new(),
+            Formula::State(state) => [[state.clone()].iter().cloned().collect()]
+                .iter()
+                .cloned()
+                .collect(),
+            Formula::And(formulas) => formulas.iter().fold(
+                [BTreeSet::new()].iter().cloned().collect(),
+                |models, formula| {
+                    let formula_models = formula.minimal_models();
+                    minimal_sets(
+                        models
+                            .iter()
+                            .flat_map(|model: &BTreeSet<String>| {
+                                formula_models
+                                    .iter()
+                                    .map(move |other| model.union(other).cloned().collect())
+                            })
+                            .collect(),
+                    )
+                },
+            ),
+            Formula::Or(formulas) => minimal_sets(
+                formulas
+                    .iter()
+                    .flat_map(|formula| formula.minimal_models())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// a word is accepted from a state when the transition formula for its first letter holds of
+// the states accepting the rest; missing transitions are false
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlternatingFiniteAutomaton {
+    pub states: BTreeSet<String>,
+    pub alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String), Formula>,
+    pub start_state: String,
+    pub accept_states: BTreeSet<String>,
+}
+
+impl AlternatingFiniteAutomaton {
+    fn formula(&self, state: &str, letter: &str) -> Formula {
+        match self
+            .transition_function
+            .get(&(state.to_string(), letter.to_string()))
+        {
+            Some(formula) => formula.clone(),
+            None => Formula::False,
+        }
+    }
+
+    // the states accepting the empty suffix are the accept states, and reading the word
+    // backwards gives the states accepting each longer suffix
+    pub fn compute(&self, input: &str) -> bool {
+        let mut accepting = self.accept_states.clone();
+
+        for symbol in input.chars().rev() {
+            let letter = symbol.to_string();
+            accepting = self
+                .states
+                .iter()
+                .filter(|state| self.formula(state, &letter).evaluate(&accepting))
+                .cloned()
+                .collect();
+        }
+
+        accepting.contains(&self.start_state)
+    }
+
+    // dual formulas and the other accept states; the complement is taken over the alphabet
+    pub fn complement(&self) -> Self {
+        let mut transition_function = BTreeMap::new();
+
+        for state in &self.states {
+            for letter in &self.alphabet {
+                transition_function.insert(
+                    (state.clone(), letter.clone()),
+                    self.formula(state, letter).dual(),
+                );
+            }
+        }
+
+        AlternatingFiniteAutomaton {
+            states: self.states.clone(),
+            alphabet: self.alphabet.clone(),
+            transition_function,
+            start_state: self.start_state.clone(),
+            accept_states: self
+                .states
+                .difference(&self.accept_states)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn rename_states(&self, rename: impl Fn(&String) -> String) -> Self {
+        AlternatingFiniteAutomaton {
+            states: self.states.iter().map(&rename).collect(),
+            alphabet: self.alphabet.clone(),
+            transition_function: self
+                .transition_function
+                .iter()
+                .map(|((state, letter), formula)| {
+                    (
+                        (rename(state), letter.clone()),
+                        formula.rename_states(&rename),
+                    )
+                })
+                .collect(),
+            start_state: rename(&self.start_state),
+            accept_states: self.accept_states.iter().map(&rename).collect(),
+        }
+    }
+
+    // every state name in use, declared or not
+    fn state_names(&self) -> BTreeSet<String> {
+        let mut states = self.states.clone();
+        states.insert(self.start_state.clone());
+        states.extend(self.accept_states.iter().cloned());
+        for ((state, _), formula) in &self.transition_function {
+            states.insert(state.clone());
+            formula.collect_states(&mut states);
+        }
+        states
+    }
+
+    // when the state names clash every state is paired with the side it comes from; the new
+    // start state joins the moves of both start states with the given connective
+    fn combine(
+        &self,
+        other: &Self,
+        connective: fn(Vec<Formula>) -> Formula,
+        start_accepts: bool,
+    ) -> Self {
+        if !self.state_names().is_disjoint(&other.state_names()) {
+            let side = |side: &'static str| {
+                move |state: &String| make_cartesian_state(state, &String::from(side))
+            };
+            return self.rename_states(side("1")).combine(
+                &other.rename_states(side("2")),
+                connective,
+                start_accepts,
+            );
+        }
+
+        let mut states: BTreeSet<String> = self.states.union(&other.states).cloned().collect();
+        let alphabet: BTreeSet<String> = self.alphabet.union(&other.alphabet).cloned().collect();
+
+        let start_state = fresh_state("q", &states);
+        states.insert(start_state.clone());
+
+        let mut transition_function = self.transition_function.clone();
+        transition_function.extend(
+            other
+                .transition_function
+                .iter()
+                .map(|(key, formula)| (key.clone(), formula.clone())),
+        );
+        for letter in &alphabet {
+            transition_function.insert(
+                (start_state.clone(), letter.clone()),
+                connective(vec![
+                    self.formula(&self.start_state, letter),
+                    other.formula(&other.start_state, letter),
+                ]),
+            );
+        }
+
+        let mut accept_states: BTreeSet<String> = self
+            .accept_states
+            .union(&other.accept_states)
+            .cloned()
+            .collect();
+        if start_accepts {
+            accept_states.insert(start_state.clone());
+        }
+
+        AlternatingFiniteAutomaton {
+            states,
+            alphabet,
+            transition_function,
+            start_state,
+            accept_states,
+        }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let start_accepts = self.accept_states.contains(&self.start_state)
+            && other.accept_states.contains(&other.start_state);
+        self.combine(other, Formula::And, start_accepts)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let start_accepts = self.accept_states.contains(&self.start_state)
+            || other.accept_states.contains(&other.start_state);
+        self.combine(other, Formula::Or, start_accepts)
+    }
+}
+
+impl From<&NondeterministicFiniteAutomata> for AlternatingFiniteAutomaton {
+    fn from(automata: &NondeterministicFiniteAutomata) -> Self {
+        let automata = automata.remove_epsilon_transitions();
+
+        AlternatingFiniteAutomaton {
+            states: automata.states.clone(),
+            alphabet: automata.alphabet.clone(),
+            transition_function: automata
+                .transition_function
+                .iter()
+                .map(|(key, targets)| {
+                    (
+                        key.clone(),
+                        Formula::Or(targets.iter().cloned().map(Formula::State).collect()),
+                    )
+                })
+                .collect(),
+            start_state: automata.start_state.clone(),
+            accept_states: automata.accept_states.clone(),
+        }
+    }
+}
+
+// a state of the nfa is a set of states that all have to accept the rest of the word; reading
+// a letter moves to any minimal set satisfying the conjunction of their formulas
+impl From<&AlternatingFiniteAutomaton> for NondeterministicFiniteAutomata {
+    fn from(automata: &AlternatingFiniteAutomaton) -> Self {
+        let start_set: BTreeSet<String> = [automata.start_state.clone()].iter().cloned().collect();
+
+        let mut states = BTreeSet::new();
+        let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        let mut queue = VecDeque::new();
+        queue.push_back(start_set.clone());
+
+        while let Some(set) = queue.pop_front() {
+            let state = set_to_state(&set);
+            if !states.insert(state.clone()) {
+                continue;
+            }
+            if set.is_subset(&automata.accept_states) {
+                accept_states.insert(state.clone());
+            }
+
+            for letter in &automata.alphabet {
+                let conjunction = Formula::And(
+                    set.iter()
+                        .map(|member| automata.formula(member, letter))
+                        .collect(),
+                );
+
+                for model in conjunction.minimal_models() {
+                    transition_function
+                        .entry((state.clone(), letter.clone()))
+                        .or_default()
+                        .insert(set_to_state(&model));
+                    queue.push_back(model);
+                }
+            }
+        }
+
+        NondeterministicFiniteAutomata {
+            states,
+            alphabet: automata.alphabet.clone(),
+            transition_function,
+            start_state: set_to_state(&start_set),
+            accept_states,
+        }
+    }
+}
+
+impl From<&AlternatingFiniteAutomaton> for DeterministicFiniteAutomata {
+    fn from(automata: &AlternatingFiniteAutomaton) -> Self {
+        NondeterministicFiniteAutomata::from(automata).determinize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::{ab, words};
+
+    #[test]
+    fn formula() {
+        let state = |name: &str| Formula::State(name.to_string());
+        let formula = Formula::And(vec![
+            Formula::Or(vec![state("p"), state("q")]),
+            Formula::Or(vec![state("p"), state("r")]),
+        ]);
+
+        let minimal_models = formula.minimal_models();
+        let models: Vec<Vec<&str>> = minimal_models
+            .iter()
+            .map(|model| model.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(models, vec![vec!["p"], vec!["q", "r"]]);
+
+        let only_q = ["q".to_string()].iter().cloned().collect();
+        assert!(!formula.evaluate(&only_q));
+        // the dual holds exactly where the formula fails on the other states
+        let p_and_r = ["p".to_string(), "r".to_string()].iter().cloned().collect();
+        assert!(formula.dual().evaluate(&p_and_r));
+        assert!(!formula.dual().evaluate(&only_q));
+
+        assert_eq!(Formula::And(vec![]).minimal_models().len(), 1);
+        assert!(Formula::Or(vec![]).minimal_models().is_empty());
+    }
+
+    #[test]
+    fn intersection_of_constraints() {
+        let constraints = [
+            catalog::contains("ab", &ab()),
+            catalog::contains("ba", &ab()),
+            catalog::ends_with("a", &ab()),
+            catalog::length_mod(2, 1, &ab()).unwrap(),
+        ];
+
+        let automata = constraints
+            .iter()
+            .map(|constraint| {
+                AlternatingFiniteAutomaton::from(&NondeterministicFiniteAutomata::from(constraint))
+            })
+            .reduce(|left, right| left.intersection(&right))
+            .unwrap();
+
+        let expected = constraints
+            .iter()
+            .skip(1)
+            .fold(constraints[0].clone(), |left, right| {
+                left.intersection(right)
+            });
+
+        for word in words(&["a", "b"], 8) {
+            assert_eq!(automata.compute(&word), expected.compute(&word));
+        }
+
+        let dfa = DeterministicFiniteAutomata::from(&automata);
+        assert_eq!(dfa.find_counterexample(&expected), None);
+        assert_eq!(
+            dfa.minimize().states.len(),
+            expected.minimize().states.len()
+        );
+    }
+
+    #[test]
+    fn complement() {
+        let alternating = |automata: &DeterministicFiniteAutomata| {
+            AlternatingFiniteAutomaton::from(&NondeterministicFiniteAutomata::from(automata))
+        };
+        let automata = alternating(&catalog::contains("aba", &ab()))
+            .union(&alternating(&catalog::ends_with("bb", &ab())));
+        let complement = automata.complement();
+
+        for word in words(&["a", "b"], 7) {
+            assert_eq!(complement.compute(&word), !automata.compute(&word));
+        }
+
+        let nfa = NondeterministicFiniteAutomata::from(&complement)
+            .simulator()
+            .unwrap();
+        for word in words(&["a", "b"], 7) {
+            assert_eq!(nfa.compute(&word), complement.compute(&word));
+        }
+    }
+
+    #[test]
+    fn combine_clashing_names() {
+        let automata = AlternatingFiniteAutomaton::from(&NondeterministicFiniteAutomata::from(
+            &catalog::contains("a", &ab()),
+        ));
+        let renamed = automata.rename_states(|state| String::from("r_") + state);
+
+        // the same names on both sides are told apart, distinct ones are kept
+        let clashing = automata.intersection(&automata.complement());
+        assert!(clashing.states.len() > automata.states.len());
+        for word in words(&["a", "b"], 5) {
+            assert!(!clashing.compute(&word));
+        }
+
+        let disjoint = automata.union(&renamed);
+        assert!(automata.states.is_subset(&disjoint.states));
+        assert!(renamed.states.is_subset(&disjoint.states));
+        for word in words(&["a", "b"], 5) {
+            assert_eq!(disjoint.compute(&word), automata.compute(&word));
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+
+fn post(
+    automata: &NondeterministicFiniteAutomata,
+    epsilon_closures: &BTreeMap<String, BTreeSet<String>>,
+    states: &BTreeSet<String>,
+    letter: &str,
+) -> BTreeSet<String> {
+    let mut next = BTreeSet::new();
+
+    for state in states {
+        if let Some(targets) = automata
+            .transition_function
+            .get(&(state.clone(), letter.to_string()))
+        {
+            for target in targets {
+                match epsilon_closures.get(target) {
+                    Some(closure) => next.extend(closure.iter().cloned()),
+                    None => {
+                        next.insert(target.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    next
+}
+
+fn closure_of(
+    epsilon_closures: &BTreeMap<String, BTreeSet<String>>,
+    state: &str,
+) -> BTreeSet<String> {
+    match epsilon_closures.get(state) {
+        Some(closure) => closure.clone(),
+        None => [state.to_string()].iter().cloned().collect(),
+    }
+}
+
+impl NondeterministicFiniteAutomata {
+    fn letters(&self) -> BTreeSet<String> {
+        self.alphabet
+            .iter()
+            .filter(|letter| *letter != "&")
+            .cloned()
+            .collect()
+    }
+
+    // breadth first over sets of states, skipping any set that contains one already explored:
+    // a word rejected from the bigger set is rejected from the smaller one at least as early,
+    // so the first rejecting set found is reached by a shortest counterexample
+    pub fn universality_counterexample(&self) -> Option<String> {
+        let epsilon_closures = self.epsilon_closures();
+        let alphabet = self.letters();
+
+        let mut antichain: Vec<BTreeSet<String>> = vec![];
+        let mut queue = VecDeque::new();
+
+        queue.push_back((
+            closure_of(&epsilon_closures, &self.start_state),
+            String::new(),
+        ));
+
+        while let Some((states, word)) = queue.pop_front() {
+            if antichain.iter().any(|explored| explored.is_subset(&states)) {
+                continue;
+            }
+            if states.is_disjoint(&self.accept_states) {
+                return Some(word);
+            }
+
+            antichain.retain(|explored| !states.is_subset(explored));
+            antichain.push(states.clone());
+
+            for letter in &alphabet {
+                queue.push_back((
+                    post(self, &epsilon_closures, &states, letter),
+                    word.clone() + letter,
+                ));
+            }
+        }
+
+        None
+    }
+
+    pub fn is_universal(&self) -> bool {
+        self.universality_counterexample().is_none()
+    }
+
+    // a shortest word accepted by self but not by other; pairs (p, S) are a state of self and
+    // the set of states other can be in after the same word, and (p, S) subsumes (p, T) when
+    // S is contained in T
+    pub fn inclusion_counterexample(&self, other: &Self) -> Option<String> {
+        let self_closures = self.epsilon_closures();
+        let other_closures = other.epsilon_closures();
+        let alphabet: BTreeSet<String> = self.letters().union(&other.letters()).cloned().collect();
+
+        let mut antichain: BTreeMap<String, Vec<BTreeSet<String>>> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        let other_start = closure_of(&other_closures, &other.start_state);
+        for state in closure_of(&self_closures, &self.start_state) {
+            queue.push_back((state, other_start.clone(), String::new()));
+        }
+
+        while let Some((state, other_states, word)) = queue.pop_front() {
+            let explored = antichain.entry(state.clone()).or_default();
+
+            if explored
+                .iter()
+                .any(|explored| explored.is_subset(&other_states))
+            {
+                continue;
+            }
+            if self.accept_states.contains(&state) && other_states.is_disjoint(&other.accept_states)
+            {
+                return Some(word);
+            }
+
+            explored.retain(|explored| !other_states.is_subset(explored));
+            explored.push(other_states.clone());
+
+            for letter in &alphabet {
+                let other_next = post(other, &other_closures, &other_states, letter);
+                let single: BTreeSet<String> = [state.clone()].iter().cloned().collect();
+
+                for next in post(self, &self_closures, &single, letter) {
+                    queue.push_back((next, other_next.clone(), word.clone() + letter));
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.inclusion_counterexample(other).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::ab;
+    use crate::regular_languages::DeterministicFiniteAutomata;
+
+    // the third letter from the end is an a
+    fn make_automata_third_from_last() -> NondeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        hash.insert(
+            (String::from("q0"), String::from("a")),
+            [String::from("q0"), String::from("q1")]
+                .iter()
+                .cloned()
+                .collect(),
+        );
+        hash.insert(
+            (String::from("q0"), String::from("b")),
+            [String::from("q0")].iter().cloned().collect(),
+        );
+        for (state, next) in &[("q1", "q2"), ("q2", "q3")] {
+            for letter in &["a", "b"] {
+                hash.insert(
+                    (state.to_string(), letter.to_string()),
+                    [next.to_string()].iter().cloned().collect(),
+                );
+            }
+        }
+
+        NondeterministicFiniteAutomata {
+            states: ["q0", "q1", "q2", "q3"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+            alphabet: ab(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q3")].iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn universality() {
+        let automata = make_automata_third_from_last();
+        assert_eq!(automata.universality_counterexample(), Some(String::new()));
+
+        let levenshtein = NondeterministicFiniteAutomata::levenshtein_automaton("a", 1, &ab());
+        assert_eq!(
+            levenshtein.universality_counterexample(),
+            Some(String::from("bb"))
+        );
+
+        let sigma_star = NondeterministicFiniteAutomata::from(&catalog::sigma_star(&ab()));
+        assert!(sigma_star.is_universal());
+
+        let mut guessing = automata.clone();
+        guessing.accept_states = guessing.states.clone();
+        assert!(guessing.is_universal());
+    }
+
+    #[test]
+    fn inclusion() {
+        let automata = make_automata_third_from_last();
+        let contains_a = NondeterministicFiniteAutomata::from(&catalog::contains("a", &ab()));
+
+        assert!(automata.is_subset_of(&contains_a));
+        assert_eq!(
+            contains_a.inclusion_counterexample(&automata),
+            Some(String::from("a"))
+        );
+
+        let ends_with_abb_or_aab = NondeterministicFiniteAutomata::from(
+            &catalog::ends_with("abb", &ab()).union(&catalog::ends_with("aab", &ab())),
+        );
+        assert!(ends_with_abb_or_aab.is_subset_of(&automata));
+        assert_eq!(
+            automata.inclusion_counterexample(&ends_with_abb_or_aab),
+            Some(String::from("aaa"))
+        );
+    }
+
+    #[test]
+    fn agrees_with_determinization() {
+        let automata = make_automata_third_from_last();
+        let levenshtein = NondeterministicFiniteAutomata::levenshtein_automaton("ab", 1, &ab());
+
+        let empty = DeterministicFiniteAutomata::from_sorted_words(Vec::<String>::new()).unwrap();
+
+        for (left, right) in &[(&automata, &levenshtein), (&levenshtein, &automata)] {
+            let counterexample = left.inclusion_counterexample(right).unwrap();
+
+            let difference = left
+                .determinize()
+                .intersection(&right.determinize().complement());
+            let expected = difference.find_counterexample(&empty).unwrap();
+
+            assert_eq!(counterexample.len(), expected.len());
+            assert!(left.simulator().unwrap().compute(&counterexample));
+            assert!(!right.simulator().unwrap().compute(&counterexample));
+        }
+
+        assert!(NondeterministicFiniteAutomata::from(&empty).is_subset_of(&automata));
+    }
+}
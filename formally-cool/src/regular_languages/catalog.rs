@@ -0,0 +1,271 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+fn state(i: usize) -> String {
+    String::from("q") + &i.to_string()
+}
+
+fn extend_alphabet(alphabet: &BTreeSet<String>, word: &str) -> BTreeSet<String> {
+    alphabet
+        .iter()
+        .cloned()
+        .chain(word.chars().map(|symbol| symbol.to_string()))
+        .collect()
+}
+
+// canonical names so equal languages always come out as the same automaton
+fn minimal(automata: &DeterministicFiniteAutomata) -> DeterministicFiniteAutomata {
+    automata.minimize_complete().canonicalize()
+}
+
+// state i has matched the first i letters of word, reading a letter moves to the longest prefix
+// of word that is a suffix of what has been read
+fn prefix_matcher(word: &str, alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+    let symbols: Vec<String> = word.chars().map(|symbol| symbol.to_string()).collect();
+
+    let mut transition_function = BTreeMap::new();
+
+    for matched in 0..=symbols.len() {
+        for letter in alphabet {
+            let mut read: Vec<&String> = symbols[..matched].iter().collect();
+            read.push(letter);
+
+            let out_state = (0..=read.len().min(symbols.len()))
+                .rev()
+                .find(|length| {
+                    read[read.len() - length..]
+                        .iter()
+                        .zip(&symbols[..*length])
+                        .all(|(left, right)| *left == right)
+                })
+                .unwrap_or(0);
+
+            transition_function.insert((state(matched), letter.clone()), state(out_state));
+        }
+    }
+
+    DeterministicFiniteAutomata {
+        states: (0..=symbols.len()).map(state).collect(),
+        alphabet: alphabet.clone(),
+        transition_function,
+        start_state: state(0),
+        accept_states: [state(symbols.len())].iter().cloned().collect(),
+    }
+}
+
+pub fn sigma_star(alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+    DeterministicFiniteAutomata {
+        states: [state(0)].iter().cloned().collect(),
+        alphabet: alphabet.clone(),
+        transition_function: alphabet
+            .iter()
+            .map(|letter| ((state(0), letter.clone()), state(0)))
+            .collect(),
+        start_state: state(0),
+        accept_states: [state(0)].iter().cloned().collect(),
+    }
+}
+
+pub fn contains(word: &str, alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+    let alphabet = extend_alphabet(alphabet, word);
+    let mut automata = prefix_matcher(word, &alphabet);
+
+    let found = state(word.chars().count());
+    for letter in &alphabet {
+        automata
+            .transition_function
+            .insert((found.clone(), letter.clone()), found.clone());
+    }
+
+    minimal(&automata)
+}
+
+pub fn starts_with(word: &str, alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+    let alphabet = extend_alphabet(alphabet, word);
+    let symbols: Vec<String> = word.chars().map(|symbol| symbol.to_string()).collect();
+
+    let mut transition_function = BTreeMap::new();
+
+    for (i, symbol) in symbols.iter().enumerate() {
+        transition_function.insert((state(i), symbol.clone()), state(i + 1));
+    }
+    for letter in &alphabet {
+        transition_function.insert((state(symbols.len()), letter.clone()), state(symbols.len()));
+    }
+
+    minimal(&DeterministicFiniteAutomata {
+        states: (0..=symbols.len()).map(state).collect(),
+        alphabet,
+        transition_function,
+        start_state: state(0),
+        accept_states: [state(symbols.len())].iter().cloned().collect(),
+    })
+}
+
+pub fn ends_with(word: &str, alphabet: &BTreeSet<String>) -> DeterministicFiniteAutomata {
+    minimal(&prefix_matcher(word, &extend_alphabet(alphabet, word)))
+}
+
+// None when modulus is zero or remainder is not below it
+pub fn length_mod(
+    modulus: usize,
+    remainder: usize,
+    alphabet: &BTreeSet<String>,
+) -> Option<DeterministicFiniteAutomata> {
+    if remainder >= modulus {
+        return None;
+    }
+
+    let mut transition_function = BTreeMap::new();
+
+    for i in 0..modulus {
+        for letter in alphabet {
+            transition_function.insert((state(i), letter.clone()), state((i + 1) % modulus));
+        }
+    }
+
+    Some(minimal(&DeterministicFiniteAutomata {
+        states: (0..modulus).map(state).collect(),
+        alphabet: alphabet.clone(),
+        transition_function,
+        start_state: state(0),
+        accept_states: [state(remainder)].iter().cloned().collect(),
+    }))
+}
+
+// numerals written most significant digit first with the digits 0-9 then a-z, the empty word
+// reads as zero; None when divisor is zero or base is not between 2 and 36
+pub fn divisible_by(divisor: u32, base: u32) -> Option<DeterministicFiniteAutomata> {
+    if divisor == 0 || !(2..=36).contains(&base) {
+        return None;
+    }
+
+    let mut transition_function = BTreeMap::new();
+
+    for remainder in 0..divisor {
+        for digit in 0..base {
+            let letter = std::char::from_digit(digit, base).unwrap().to_string();
+            let out_state =
+                ((remainder as u64 * base as u64 + digit as u64) % divisor as u64) as usize;
+
+            transition_function.insert((state(remainder as usize), letter), state(out_state));
+        }
+    }
+
+    Some(minimal(&DeterministicFiniteAutomata {
+        states: (0..divisor as usize).map(state).collect(),
+        alphabet: (0..base)
+            .map(|digit| std::char::from_digit(digit, base).unwrap().to_string())
+            .collect(),
+        transition_function,
+        start_state: state(0),
+        accept_states: [state(0)].iter().cloned().collect(),
+    }))
+}
+
+pub fn exactly_n_occurrences(
+    letter: &str,
+    occurrences: usize,
+    alphabet: &BTreeSet<String>,
+) -> DeterministicFiniteAutomata {
+    let mut alphabet = alphabet.clone();
+    alphabet.insert(letter.to_string());
+
+    let mut transition_function = BTreeMap::new();
+
+    // the extra state counts every occurrence past the limit
+    for count in 0..=occurrences + 1 {
+        for other in &alphabet {
+            let out_state = if other == letter {
+                (count + 1).min(occurrences + 1)
+            } else {
+                count
+            };
+            transition_function.insert((state(count), other.clone()), state(out_state));
+        }
+    }
+
+    minimal(&DeterministicFiniteAutomata {
+        states: (0..=occurrences + 1).map(state).collect(),
+        alphabet,
+        transition_function,
+        start_state: state(0),
+        accept_states: [state(occurrences)].iter().cloned().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::{ab, words};
+
+    fn assert_minimal(automata: &DeterministicFiniteAutomata) {
+        assert_eq!(
+            automata.myhill_nerode_report().classes.len(),
+            automata.states.len()
+        );
+    }
+
+    #[test]
+    fn word_constructors() {
+        let contains = contains("aba", &ab());
+        let starts_with = starts_with("aba", &ab());
+        let ends_with = ends_with("aba", &ab());
+
+        for word in words(&["a", "b"], 7) {
+            assert_eq!(contains.compute(&word), word.contains("aba"));
+            assert_eq!(starts_with.compute(&word), word.starts_with("aba"));
+            assert_eq!(ends_with.compute(&word), word.ends_with("aba"));
+        }
+
+        assert_eq!(contains.states.len(), 4);
+        assert_eq!(starts_with.states.len(), 5);
+        assert_eq!(ends_with.states.len(), 4);
+
+        assert_minimal(&contains);
+        assert_minimal(&starts_with);
+        assert_minimal(&ends_with);
+    }
+
+    #[test]
+    fn counting_constructors() {
+        let length_mod = length_mod(3, 1, &ab()).unwrap();
+        let exactly_two = exactly_n_occurrences("b", 2, &ab());
+        let sigma_star = sigma_star(&ab());
+
+        for word in words(&["a", "b"], 7) {
+            assert_eq!(length_mod.compute(&word), word.len() % 3 == 1);
+            assert_eq!(exactly_two.compute(&word), word.matches('b').count() == 2);
+            assert!(sigma_star.compute(&word));
+        }
+
+        assert_minimal(&length_mod);
+        assert_minimal(&exactly_two);
+        assert_minimal(&sigma_star);
+        assert!(super::length_mod(0, 0, &ab()).is_none());
+        assert!(super::length_mod(3, 3, &ab()).is_none());
+        assert!(super::length_mod(3, 2, &ab()).is_some());
+    }
+
+    #[test]
+    fn divisible_by() {
+        let by_three = super::divisible_by(3, 2).unwrap();
+
+        for value in 0..64u32 {
+            assert_eq!(by_three.compute(&format!("{:b}", value)), value % 3 == 0);
+        }
+        assert_eq!(by_three.states.len(), 3);
+
+        // only the last two digits matter, so the residues modulo 4 collapse into 3 states
+        let by_four = super::divisible_by(4, 10).unwrap();
+
+        for value in 0..1000u32 {
+            assert_eq!(by_four.compute(&value.to_string()), value % 4 == 0);
+        }
+        assert_eq!(by_four.states.len(), 3);
+        assert_minimal(&by_four);
+
+        assert!(super::divisible_by(3, 1).is_none());
+    }
+}
@@ -0,0 +1,311 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use super::matcher::MatchPosition;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+const NO_COLUMN: u32 = u32::MAX;
+
+fn bitset_contains(bitset: &[u64], index: u32) -> bool {
+    bitset[index as usize / 64] & (1 << (index % 64)) != 0
+}
+
+fn bitset_insert(bitset: &mut [u64], index: u32) {
+    bitset[index as usize / 64] |= 1 << (index % 64);
+}
+
+// row i of the table holds the transitions of state i, one column per letter; the extra last row
+// is a sink standing for every missing transition and every letter outside the alphabet
+#[derive(Debug, Clone)]
+pub struct CompiledDeterministicFiniteAutomata {
+    states: Vec<String>,
+    alphabet: Vec<String>,
+    ascii_columns: Vec<u32>,
+    columns: BTreeMap<char, u32>,
+    table: Vec<u32>,
+    accepting: Vec<u64>,
+    productive: Vec<u64>,
+    start_state: u32,
+    sink: u32,
+}
+
+impl CompiledDeterministicFiniteAutomata {
+    fn column(&self, symbol: char) -> u32 {
+        if symbol.is_ascii() {
+            self.ascii_columns[symbol as usize]
+        } else {
+            match self.columns.get(&symbol) {
+                Some(column) => *column,
+                None => NO_COLUMN,
+            }
+        }
+    }
+
+    fn step(&self, state: u32, symbol: char) -> u32 {
+        match self.column(symbol) {
+            NO_COLUMN => self.sink,
+            column => self.table[state as usize * self.alphabet.len() + column as usize],
+        }
+    }
+
+    pub fn compute(&self, input: &str) -> bool {
+        let mut state = self.start_state;
+
+        for symbol in input.chars() {
+            state = self.step(state, symbol);
+            if state == self.sink {
+                return false;
+            }
+        }
+
+        bitset_contains(&self.accepting, state)
+    }
+
+    // leftmost-longest non-empty matches as byte offsets, like Matcher does over a stream; with
+    // a window limit an attempt stops after that many characters, so a failed one costs at most
+    // that many steps, but a longer match is cut to its longest accepted prefix that fits, or
+    // missed when there is none
+    pub fn find_all(&self, text: &str, window_limit: Option<usize>) -> Vec<MatchPosition> {
+        let window_limit = window_limit.unwrap_or(usize::MAX);
+        let mut matches = vec![];
+        let mut start = 0;
+
+        while start < text.len() {
+            let mut state = self.start_state;
+            let mut end = None;
+
+            for (offset, symbol) in text[start..].char_indices().take(window_limit) {
+                if !bitset_contains(&self.productive, state) {
+                    break;
+                }
+
+                state = self.step(state, symbol);
+
+                if bitset_contains(&self.accepting, state) {
+                    end = Some(start + offset + symbol.len_utf8());
+                }
+            }
+
+            match end {
+                Some(end) => {
+                    matches.push(MatchPosition { start, end });
+                    start = end;
+                }
+                None => {
+                    start += text[start..].chars().next().unwrap().len_utf8();
+                }
+            }
+        }
+
+        matches
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    // states only mentioned by the start state or a transition get a row of their own too
+    pub fn compile(&self) -> CompiledDeterministicFiniteAutomata {
+        let mut states: BTreeSet<String> = self.states.clone();
+        states.insert(self.start_state.clone());
+        states.extend(
+            self.transition_function
+                .keys()
+                .map(|(state, _)| state.clone()),
+        );
+        states.extend(self.transition_function.values().cloned());
+
+        let states: Vec<String> = states.into_iter().collect();
+        let alphabet: Vec<String> = self.alphabet.iter().cloned().collect();
+
+        let index: BTreeMap<&String, u32> = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state, i as u32))
+            .collect();
+        let sink = states.len() as u32;
+
+        let mut ascii_columns = vec![NO_COLUMN; 128];
+        let mut columns = BTreeMap::new();
+
+        for (column, letter) in alphabet.iter().enumerate() {
+            let mut symbols = letter.chars();
+            if let (Some(symbol), None) = (symbols.next(), symbols.next()) {
+                if symbol.is_ascii() {
+                    ascii_columns[symbol as usize] = column as u32;
+                } else {
+                    columns.insert(symbol, column as u32);
+                }
+            }
+        }
+
+        let mut table = vec![sink; (states.len() + 1) * alphabet.len()];
+
+        for ((state, letter), out_state) in &self.transition_function {
+            if let Some(column) = alphabet.iter().position(|other| other == letter) {
+                table[index[state] as usize * alphabet.len() + column] = index[out_state];
+            }
+        }
+
+        let mut accepting = vec![0; states.len() / 64 + 1];
+        for state in &self.accept_states {
+            if let Some(state) = index.get(state) {
+                bitset_insert(&mut accepting, *state);
+            }
+        }
+
+        let mut productive = accepting.clone();
+        let mut stop = false;
+        while !stop {
+            stop = true;
+            for state in 0..sink {
+                if bitset_contains(&productive, state) {
+                    continue;
+                }
+                let row =
+                    &table[state as usize * alphabet.len()..(state as usize + 1) * alphabet.len()];
+                if row
+                    .iter()
+                    .any(|out_state| bitset_contains(&productive, *out_state))
+                {
+                    bitset_insert(&mut productive, state);
+                    stop = false;
+                }
+            }
+        }
+
+        CompiledDeterministicFiniteAutomata {
+            start_state: index[&self.start_state],
+            states,
+            alphabet,
+            ascii_columns,
+            columns,
+            table,
+            accepting,
+            productive,
+            sink,
+        }
+    }
+}
+
+impl From<&CompiledDeterministicFiniteAutomata> for DeterministicFiniteAutomata {
+    fn from(automata: &CompiledDeterministicFiniteAutomata) -> Self {
+        let mut transition_function = BTreeMap::new();
+
+        for (state, name) in automata.states.iter().enumerate() {
+            for (column, letter) in automata.alphabet.iter().enumerate() {
+                let out_state = automata.table[state * automata.alphabet.len() + column];
+                if out_state != automata.sink {
+                    transition_function.insert(
+                        (name.clone(), letter.clone()),
+                        automata.states[out_state as usize].clone(),
+                    );
+                }
+            }
+        }
+
+        DeterministicFiniteAutomata {
+            states: automata.states.iter().cloned().collect(),
+            alphabet: automata.alphabet.iter().cloned().collect(),
+            transition_function,
+            start_state: automata.states[automata.start_state as usize].clone(),
+            accept_states: (0..automata.sink)
+                .filter(|state| bitset_contains(&automata.accepting, *state))
+                .map(|state| automata.states[state as usize].clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+
+    #[test]
+    fn compute() {
+        let alphabet = ["a", "b"].iter().map(|x| x.to_string()).collect();
+        let automata =
+            catalog::contains("abba", &alphabet).intersection(&catalog::ends_with("b", &alphabet));
+
+        let compiled = automata.compile();
+
+        let mut words = vec![String::new()];
+        for _ in 0..8 {
+            words = words
+                .iter()
+                .flat_map(|word| vec![word.clone() + "a", word.clone() + "b"])
+                .collect();
+
+            for word in &words {
+                assert_eq!(compiled.compute(word), automata.compute(word));
+            }
+        }
+
+        assert!(!compiled.compute("abbac"));
+        assert!(compiled.compute("abbab"));
+    }
+
+    #[test]
+    fn find_all() {
+        let automata =
+            DeterministicFiniteAutomata::from_sorted_words(["a", "abcd", "é", "éé"]).unwrap();
+
+        let text = "abcxabcdabc-ééé";
+
+        assert_eq!(
+            automata.compile().find_all(text, None),
+            automata.matcher().read_from(text.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn undeclared_states() {
+        let mut automata = DeterministicFiniteAutomata::from_sorted_words(["ab"]).unwrap();
+
+        automata.states.clear();
+        automata.accept_states.insert(String::from("elsewhere"));
+
+        let compiled = automata.compile();
+
+        assert!(compiled.compute("ab"));
+        assert!(!compiled.compute("a"));
+    }
+
+    #[test]
+    fn find_all_window_limit() {
+        // a, any number of b and then c
+        let mut automata = DeterministicFiniteAutomata::from_sorted_words(["ac"]).unwrap();
+        let after_a =
+            automata.transition_function[&(String::from("q0"), String::from("a"))].clone();
+        automata.alphabet.insert(String::from("b"));
+        automata
+            .transition_function
+            .insert((after_a.clone(), String::from("b")), after_a);
+
+        let compiled = automata.compile();
+
+        let short = String::from("x") + &"b".repeat(10) + "abbc";
+        assert_eq!(
+            compiled.find_all(&short, Some(4)),
+            vec![MatchPosition { start: 11, end: 15 }]
+        );
+
+        let long = String::from("a") + &"b".repeat(100) + "c";
+        assert_eq!(
+            compiled.find_all(&long, None),
+            vec![MatchPosition { start: 0, end: 102 }]
+        );
+        assert_eq!(compiled.find_all(&long, Some(100)), vec![]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let automata = DeterministicFiniteAutomata::from_sorted_words(["ab", "b", "ba"]).unwrap();
+
+        let restored = DeterministicFiniteAutomata::from(&automata.compile());
+
+        assert_eq!(restored.states, automata.states);
+        assert_eq!(restored.alphabet, automata.alphabet);
+        assert_eq!(restored.transition_function, automata.transition_function);
+        assert_eq!(restored.start_state, automata.start_state);
+        assert_eq!(restored.accept_states, automata.accept_states);
+    }
+}
@@ -0,0 +1,224 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        String::from("\"") + &field.replace('"', "\"\"") + "\""
+    } else {
+        field.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConformanceTest {
+    pub input: String,
+    pub accept: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConformanceTestSuite {
+    pub tests: Vec<ConformanceTest>,
+}
+
+impl ConformanceTestSuite {
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("input,verdict\n");
+
+        for test in &self.tests {
+            csv += &csv_field(&test.input);
+            csv += if test.accept {
+                ",accept\n"
+            } else {
+                ",reject\n"
+            };
+        }
+
+        csv
+    }
+
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn transition_cover(&self) -> BTreeSet<String> {
+        let specification = self.minimize_complete();
+
+        let mut cover = BTreeSet::new();
+
+        for access_string in specification.access_strings().values() {
+            cover.insert(access_string.clone());
+            for letter in &specification.alphabet {
+                cover.insert(access_string.clone() + letter);
+            }
+        }
+
+        cover
+    }
+
+    pub fn characterization_set(&self) -> BTreeSet<String> {
+        let mut characterization_set: BTreeSet<String> = self
+            .minimize_complete()
+            .distinguishing_suffixes()
+            .values()
+            .cloned()
+            .collect();
+
+        characterization_set.insert(String::new());
+
+        characterization_set
+    }
+
+    // W-method: transition cover, then every word of length up to extra_states, then W; without
+    // a bound the implementation is taken to be as large as the minimal specification
+    pub fn conformance_test_suite(&self, extra_states: Option<usize>) -> ConformanceTestSuite {
+        let extra_states = extra_states.unwrap_or(0);
+        let transition_cover = self.transition_cover();
+        let characterization_set = self.characterization_set();
+
+        let mut middle = vec![String::new()];
+        let mut last_layer = vec![String::new()];
+
+        for _ in 0..extra_states {
+            let mut layer = vec![];
+            for word in &last_layer {
+                for letter in &self.alphabet {
+                    layer.push(word.clone() + letter);
+                }
+            }
+            middle.extend(layer.iter().cloned());
+            last_layer = layer;
+        }
+
+        let mut inputs = BTreeSet::new();
+
+        for prefix in &transition_cover {
+            for infix in &middle {
+                for suffix in &characterization_set {
+                    inputs.insert(prefix.clone() + infix + suffix);
+                }
+            }
+        }
+
+        let mut inputs: Vec<String> = inputs.into_iter().collect();
+        inputs.sort_by(|left, right| (left.len(), left).cmp(&(right.len(), right)));
+
+        ConformanceTestSuite {
+            tests: inputs
+                .into_iter()
+                .map(|input| ConformanceTest {
+                    accept: self.compute(&input),
+                    input,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::make_automata_ends_with_ab;
+
+    fn passes(suite: &ConformanceTestSuite, implementation: &DeterministicFiniteAutomata) -> bool {
+        suite
+            .tests
+            .iter()
+            .all(|test| implementation.compute(&test.input) == test.accept)
+    }
+
+    fn differs(left: &DeterministicFiniteAutomata, right: &DeterministicFiniteAutomata) -> bool {
+        let mut words = vec![String::new()];
+
+        for _ in 0..6 {
+            words = words
+                .iter()
+                .flat_map(|word| vec![word.clone() + "a", word.clone() + "b"])
+                .collect();
+
+            if words
+                .iter()
+                .any(|word| left.compute(word) != right.compute(word))
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    #[test]
+    fn conformance_test_suite() {
+        let automata = make_automata_ends_with_ab();
+
+        assert_eq!(automata.transition_cover().len(), 7);
+        assert_eq!(automata.characterization_set().len(), 2);
+
+        let suite = automata.conformance_test_suite(None);
+
+        assert!(passes(&suite, &automata));
+        assert_eq!(
+            suite.tests[0],
+            ConformanceTest {
+                input: String::from(""),
+                accept: false,
+            }
+        );
+
+        for (state, letter) in automata.transition_function.keys() {
+            for out_state in &automata.states {
+                let mut mutant = automata.clone();
+                mutant
+                    .transition_function
+                    .insert((state.clone(), letter.clone()), out_state.clone());
+
+                if differs(&automata, &mutant) {
+                    assert!(!passes(&suite, &mutant));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn conformance_test_suite_extra_states() {
+        let automata = make_automata_ends_with_ab();
+
+        // one hidden extra state that only misbehaves after reading "abb"
+        let mut mutant = automata.clone();
+        mutant.states.insert(String::from("q3"));
+        mutant
+            .transition_function
+            .insert((String::from("q2"), String::from("b")), String::from("q3"));
+        mutant
+            .transition_function
+            .insert((String::from("q3"), String::from("a")), String::from("q2"));
+        mutant
+            .transition_function
+            .insert((String::from("q3"), String::from("b")), String::from("q0"));
+
+        assert!(passes(&automata.conformance_test_suite(None), &mutant));
+        assert!(!passes(&automata.conformance_test_suite(Some(1)), &mutant));
+    }
+
+    #[test]
+    fn export() {
+        let suite = make_automata_ends_with_ab().conformance_test_suite(None);
+
+        let csv = suite.to_csv();
+
+        assert!(csv.starts_with("input,verdict\n,reject\na,reject\nb,reject\n"));
+        assert!(csv.contains("\nab,accept\n"));
+        assert_eq!(csv.lines().count(), suite.tests.len() + 1);
+
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+        assert_eq!(csv_field("ab"), "ab");
+
+        let yaml = suite.to_yaml().unwrap();
+        let parsed: ConformanceTestSuite = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(parsed.tests, suite.tests);
+    }
+}
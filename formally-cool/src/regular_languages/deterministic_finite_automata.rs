@@ -2,6 +2,7 @@ use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::collections::VecDeque;
 use std::fmt;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -154,6 +155,14 @@ pub fn set_to_state(set: &BTreeSet<String>) -> String {
     concatenated_state
 }
 
+// the prefix followed by the smallest number giving a name not taken yet
+pub fn fresh_state(prefix: &str, taken: &BTreeSet<String>) -> String {
+    (0..)
+        .map(|i: usize| String::from(prefix) + &i.to_string())
+        .find(|state| !taken.contains(state))
+        .unwrap()
+}
+
 pub fn make_cartesian_state(left: &String, right: &String) -> String {
     String::from("(") + left + ", " + right + ")"
 }
@@ -206,7 +215,13 @@ impl DeterministicFiniteAutomata {
     pub fn compute(&self, input: &str) -> bool {
         let mut actual_state = self.start_state.clone();
         for symbol in input.chars() {
-            actual_state = self.transition_function[&(actual_state, symbol.to_string())].clone();
+            match self
+                .transition_function
+                .get(&(actual_state, symbol.to_string()))
+            {
+                Some(state) => actual_state = state.clone(),
+                None => return false,
+            }
         }
         self.accept_states.contains(&actual_state)
     }
@@ -284,7 +299,7 @@ impl DeterministicFiniteAutomata {
         {
             let mut before = accept_states.clone();
 
-            let mut after = BTreeSet::new();
+            let mut after = accept_states.clone();
 
             let mut stop = false;
             while !stop {
@@ -326,13 +341,17 @@ impl DeterministicFiniteAutomata {
         let mut start_state = String::new();
         let mut accept_states = BTreeSet::new();
 
+        // missing transitions have to be told apart from real ones, so the partition is refined
+        // on the completed automaton and its sink is left out afterwards
+        let automata = self.complete();
+
         // P := {F, Q \ F};
         let mut equivalence_classes = BTreeSet::new();
 
-        let accept_states_equivalence = self.accept_states.clone();
-        let non_accept_states_equivalence = self
+        let accept_states_equivalence = automata.accept_states.clone();
+        let non_accept_states_equivalence = automata
             .states
-            .difference(&self.accept_states)
+            .difference(&automata.accept_states)
             .cloned()
             .collect();
 
@@ -355,7 +374,7 @@ impl DeterministicFiniteAutomata {
                 // let X be the set of states for which a transition on c leads to a state in A
                 let mut auxiliar_set = BTreeSet::new();
 
-                for ((state, input), out_state) in &self.transition_function {
+                for ((state, input), out_state) in &automata.transition_function {
                     if input == letter {
                         if current_set.contains(out_state) {
                             auxiliar_set.insert(state.clone());
@@ -403,6 +422,10 @@ impl DeterministicFiniteAutomata {
                                 equivalence_classes_aux.insert(difference.clone());
                             }
                         }
+
+                        // the block now at i has not been checked yet
+                        equivalence_classes = equivalence_classes_vector.iter().cloned().collect();
+                        continue;
                     }
 
                     equivalence_classes = equivalence_classes_vector.iter().cloned().collect();
@@ -415,6 +438,17 @@ impl DeterministicFiniteAutomata {
         }
         //
 
+        let equivalence_classes: BTreeSet<BTreeSet<String>> = equivalence_classes
+            .iter()
+            .map(|equivalence_class| {
+                equivalence_class
+                    .intersection(&self.states)
+                    .cloned()
+                    .collect::<BTreeSet<String>>()
+            })
+            .filter(|equivalence_class| !equivalence_class.is_empty())
+            .collect();
+
         for equivalence_class in &equivalence_classes {
             for accept_state in &self.accept_states {
                 if equivalence_class.contains(accept_state) {
@@ -469,12 +503,104 @@ impl DeterministicFiniteAutomata {
         }
     }
 
+    pub fn complete(&self) -> Self {
+        let mut automata = self.clone();
+
+        let dead_state = fresh_state("dead_", &automata.states);
+
+        let mut is_complete = true;
+
+        for state in &self.states {
+            for letter in &self.alphabet {
+                automata
+                    .transition_function
+                    .entry((state.clone(), letter.clone()))
+                    .or_insert_with(|| {
+                        is_complete = false;
+                        dead_state.clone()
+                    });
+            }
+        }
+
+        if !is_complete {
+            for letter in &self.alphabet {
+                automata
+                    .transition_function
+                    .insert((dead_state.clone(), letter.clone()), dead_state.clone());
+            }
+            automata.states.insert(dead_state);
+        }
+
+        automata
+    }
+
+    // unreachable states are dropped and the rest renamed q0, q1, ... in the order a breadth
+    // first search from the start state meets them, following letters in alphabet order
+    pub fn canonicalize(&self) -> Self {
+        let mut names = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        let mut transition_function = BTreeMap::new();
+
+        names.insert(self.start_state.clone(), String::from("q0"));
+        queue.push_back(self.start_state.clone());
+
+        while let Some(state) = queue.pop_front() {
+            for letter in &self.alphabet {
+                if let Some(out_state) = self
+                    .transition_function
+                    .get(&(state.clone(), letter.clone()))
+                {
+                    if !names.contains_key(out_state) {
+                        let name = String::from("q") + &names.len().to_string();
+                        names.insert(out_state.clone(), name);
+                        queue.push_back(out_state.clone());
+                    }
+
+                    transition_function.insert(
+                        (names[&state].clone(), letter.clone()),
+                        names[out_state].clone(),
+                    );
+                }
+            }
+        }
+
+        DeterministicFiniteAutomata {
+            states: names.values().cloned().collect(),
+            alphabet: self.alphabet.clone(),
+            transition_function,
+            start_state: String::from("q0"),
+            accept_states: self
+                .accept_states
+                .iter()
+                .filter_map(|state| names.get(state).cloned())
+                .collect(),
+        }
+    }
+
+    // equal up to state names once unreachable states are ignored
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        let left = self.canonicalize();
+        let right = other.canonicalize();
+
+        left.states == right.states
+            && left.alphabet == right.alphabet
+            && left.transition_function == right.transition_function
+            && left.accept_states == right.accept_states
+    }
+
     pub fn minimize(&self) -> Self {
         let dfa = self.remove_unreachable_states();
         let dfa = dfa.remove_non_productive_states();
         dfa.remove_equivalent_states()
     }
 
+    // minimal automata that keeps a sink state for the missing transitions
+    pub fn minimize_complete(&self) -> Self {
+        self.remove_unreachable_states()
+            .complete()
+            .remove_equivalent_states()
+    }
+
     pub fn union(&self, other: &Self) -> Self {
         let mut states = BTreeSet::new();
 
@@ -565,152 +691,69 @@ impl DeterministicFiniteAutomata {
 
         union
     }
-}
-
-fn powerset<T: Clone>(slice: &[T]) -> Vec<Vec<T>> {
-    let mut v: Vec<Vec<T>> = Vec::new();
-
-    for mask in 0..(1 << slice.len()) {
-        let mut ss: Vec<T> = vec![];
-        let mut bitset = mask;
-        while bitset > 0 {
-            // isolate the rightmost bit to select one item
-            let rightmost: u64 = bitset & !(bitset - 1);
-            // turn the isolated bit into an array index
-            let idx = rightmost.trailing_zeros();
-            let item = (*slice.get(idx as usize).unwrap()).clone();
-            ss.push(item);
-            // zero the trailing bit
-            bitset &= bitset - 1;
-        }
-        v.push(ss);
-    }
-    v
-}
-
-impl From<&NondeterministicFiniteAutomata> for DeterministicFiniteAutomata {
-    fn from(automata: &NondeterministicFiniteAutomata) -> Self {
-        let mut states = BTreeSet::new();
-        let mut alphabet = BTreeSet::new();
-        let mut transition_function = BTreeMap::new();
-        let mut start_state;
-        let mut accept_states = BTreeSet::new();
-
-        for letter in &automata.alphabet {
-            alphabet.insert(letter.clone());
-        }
-
-        {
-            let mut state_set_vec = vec![];
-
-            for state in &automata.states {
-                state_set_vec.push(state.clone());
-            }
-
-            let power_state_set_vec = powerset(&state_set_vec);
-
-            for vec in &power_state_set_vec {
-                let set: BTreeSet<_> = vec.iter().cloned().collect();
 
-                let state_name = set_to_state(&set);
-
-                states.insert(state_name);
-            }
-        }
+    pub fn find_counterexample(&self, other: &Self) -> Option<String> {
+        let alphabet: BTreeSet<String> = self.alphabet.union(&other.alphabet).cloned().collect();
 
-        let mut epsilon_closure = BTreeMap::new();
+        // None stands for having fallen off the automata through a missing transition
+        let step = |automata: &Self, state: &Option<String>, letter: &String| match state {
+            Some(state) => automata
+                .transition_function
+                .get(&(state.clone(), letter.clone()))
+                .cloned(),
+            None => None,
+        };
 
-        {
-            for state in &automata.states {
-                let mut before = BTreeSet::new();
+        let accepts = |automata: &Self, state: &Option<String>| match state {
+            Some(state) => automata.accept_states.contains(state),
+            None => false,
+        };
 
-                before.insert(state.clone());
+        let start = (
+            Some(self.start_state.clone()),
+            Some(other.start_state.clone()),
+        );
 
-                let mut after = BTreeSet::new();
+        let mut words = BTreeMap::new();
+        let mut queue = VecDeque::new();
 
-                let mut stop = false;
-                while !stop {
-                    after = BTreeSet::new();
+        words.insert(start.clone(), String::new());
+        queue.push_back(start);
 
-                    for state in &before {
-                        after.insert(state.clone());
+        while let Some((left_state, right_state)) = queue.pop_front() {
+            let word = words[&(left_state.clone(), right_state.clone())].clone();
 
-                        match automata
-                            .transition_function
-                            .get(&(state.clone(), "&".to_string()))
-                        {
-                            Some(set) => {
-                                for state in set {
-                                    after.insert(state.clone());
-                                }
-                            }
-                            None => (),
-                        }
-                    }
-
-                    if after.is_subset(&before) && after.is_superset(&before) {
-                        stop = true;
-                    } else {
-                        before = after.clone();
-                    }
-                }
-
-                epsilon_closure.insert(state.clone(), after);
+            if accepts(self, &left_state) != accepts(other, &right_state) {
+                return Some(word);
             }
-        }
 
-        for state in &states {
             for letter in &alphabet {
-                let mut output_state_set = BTreeSet::new();
-
-                let split_state_names = state_to_set(state);
-
-                for state_name in split_state_names {
-                    if automata.accept_states.contains(&state_name) {
-                        accept_states.insert(state.clone());
-                    }
-                    match automata
-                        .transition_function
-                        .get(&(state_name.to_owned(), letter.clone()))
-                    {
-                        Some(set) => {
-                            for state in set {
-                                let epsilon_closure_for_state = epsilon_closure.get(state).unwrap();
-
-                                for state in epsilon_closure_for_state {
-                                    output_state_set.insert(state.clone());
-                                }
-                            }
-                        }
-                        None => (),
-                    }
-                }
-
-                let output_state_name = set_to_state(&output_state_set);
-
-                transition_function.insert((state.clone(), letter.clone()), output_state_name);
+                let next = (
+                    step(self, &left_state, letter),
+                    step(other, &right_state, letter),
+                );
+
+                words.entry(next.clone()).or_insert_with(|| {
+                    queue.push_back(next);
+                    word.clone() + letter
+                });
             }
         }
 
-        {
-            let start_state_closure = epsilon_closure.get(&automata.start_state).unwrap();
-
-            start_state = set_to_state(start_state_closure);
-        }
+        None
+    }
+}
 
-        DeterministicFiniteAutomata {
-            states: states,
-            alphabet: alphabet,
-            transition_function: transition_function,
-            start_state: start_state,
-            accept_states: accept_states,
-        }
+impl From<&NondeterministicFiniteAutomata> for DeterministicFiniteAutomata {
+    fn from(automata: &NondeterministicFiniteAutomata) -> Self {
+        automata.determinize()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::regular_languages::fixtures::{ab, words};
 
     #[test]
     fn cartesian() {
@@ -1236,4 +1279,279 @@ mod tests {
 
         assert_eq!(automata_union.accept_states, union_accept_states);
     }
+
+    #[test]
+    fn find_counterexample() {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q0"), String::from("b")), String::from("q0"));
+        hash.insert((String::from("q1"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q0"));
+
+        let states = ["q0".to_string(), "q1".to_string()]
+            .iter()
+            .cloned()
+            .collect();
+
+        let alphabet = ["a".to_string(), "b".to_string()].iter().cloned().collect();
+
+        let accept_states = [String::from("q1")].iter().cloned().collect();
+
+        let automata_ends_with_a = DeterministicFiniteAutomata {
+            states,
+            alphabet,
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states,
+        };
+
+        assert_eq!(
+            automata_ends_with_a.find_counterexample(&automata_ends_with_a.minimize()),
+            None
+        );
+
+        let mut automata_ends_with_aa = automata_ends_with_a.clone();
+
+        automata_ends_with_aa.states.insert(String::from("q2"));
+        automata_ends_with_aa
+            .transition_function
+            .insert((String::from("q1"), String::from("a")), String::from("q2"));
+        automata_ends_with_aa
+            .transition_function
+            .insert((String::from("q2"), String::from("a")), String::from("q2"));
+        automata_ends_with_aa.accept_states = [String::from("q2")].iter().cloned().collect();
+
+        assert_eq!(
+            automata_ends_with_a.find_counterexample(&automata_ends_with_aa),
+            Some(String::from("a"))
+        );
+
+        automata_ends_with_aa
+            .accept_states
+            .insert(String::from("q1"));
+
+        assert_eq!(
+            automata_ends_with_a.find_counterexample(&automata_ends_with_aa),
+            Some(String::from("aaba"))
+        );
+    }
+
+    #[test]
+    fn canonicalize() {
+        let mut hash = BTreeMap::new();
+
+        hash.insert(
+            (String::from("q0"), String::from("a")),
+            [String::from("q0"), String::from("q1")]
+                .iter()
+                .cloned()
+                .collect(),
+        );
+        hash.insert(
+            (String::from("q0"), String::from("b")),
+            [String::from("q0")].iter().cloned().collect(),
+        );
+
+        let automata_ends_with_a = NondeterministicFiniteAutomata {
+            states: ["q0".to_string(), "q1".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q1")].iter().cloned().collect(),
+        };
+
+        let determinized = DeterministicFiniteAutomata::from(&automata_ends_with_a);
+        let canonical = determinized.canonicalize();
+
+        assert_eq!(determinized.start_state, "(q0)");
+        assert_eq!(canonical.states.len(), 2);
+        assert_eq!(
+            canonical.transition_function[&(String::from("q0"), String::from("a"))],
+            "q1"
+        );
+        assert_eq!(
+            canonical.accept_states,
+            [String::from("q1")].iter().cloned().collect()
+        );
+
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("x"), String::from("a")), String::from("y"));
+        hash.insert((String::from("x"), String::from("b")), String::from("x"));
+        hash.insert((String::from("y"), String::from("a")), String::from("y"));
+        hash.insert((String::from("y"), String::from("b")), String::from("x"));
+
+        let mut hand_built = DeterministicFiniteAutomata {
+            states: ["x".to_string(), "y".to_string(), "z".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("x"),
+            accept_states: [String::from("y")].iter().cloned().collect(),
+        };
+
+        assert!(determinized.is_isomorphic(&hand_built));
+
+        hand_built.accept_states.insert(String::from("x"));
+
+        assert!(!determinized.is_isomorphic(&hand_built));
+    }
+
+    #[test]
+    fn compute_partial() {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q0"));
+
+        let automata = DeterministicFiniteAutomata {
+            states: ["q0".to_string(), "q1".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q0")].iter().cloned().collect(),
+        };
+
+        assert!(automata.compute("abab"));
+        // falling off through a missing transition rejects instead of panicking
+        assert!(!automata.compute("aa"));
+        assert!(!automata.compute("b"));
+        assert!(!automata.compute("ac"));
+    }
+
+    #[test]
+    fn minimize_partial() {
+        // q0 and q1 only differ through the b transition missing at q0, and q2 is not accepting
+        // but still productive
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("a")), String::from("q0"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q2"));
+        hash.insert((String::from("q2"), String::from("a")), String::from("q2"));
+        hash.insert((String::from("q2"), String::from("b")), String::from("q1"));
+
+        let automata = DeterministicFiniteAutomata {
+            states: ["q0".to_string(), "q1".to_string(), "q2".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q0"), String::from("q1")]
+                .iter()
+                .cloned()
+                .collect(),
+        };
+
+        let minimized = automata.minimize();
+
+        assert_eq!(automata.find_counterexample(&minimized), None);
+        assert_eq!(minimized.states.len(), 3);
+    }
+
+    #[test]
+    fn minimize_split_recheck() {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q0"));
+        hash.insert((String::from("q0"), String::from("b")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q1"));
+        hash.insert((String::from("q2"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q2"), String::from("b")), String::from("q1"));
+        hash.insert((String::from("q3"), String::from("a")), String::from("q0"));
+        hash.insert((String::from("q3"), String::from("b")), String::from("q3"));
+
+        let automata = DeterministicFiniteAutomata {
+            states: [
+                "q0".to_string(),
+                "q1".to_string(),
+                "q2".to_string(),
+                "q3".to_string(),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q1"), String::from("q3")]
+                .iter()
+                .cloned()
+                .collect(),
+        };
+
+        // a split moves the next block into the checked position, which must not be skipped
+        let reduced = automata.remove_equivalent_states();
+
+        assert_eq!(automata.find_counterexample(&reduced), None);
+        assert_eq!(reduced.states.len(), 4);
+    }
+
+    #[test]
+    fn from_nondeterministic() {
+        let mut hash = BTreeMap::new();
+
+        hash.insert(
+            (String::from("q0"), String::from("&")),
+            [String::from("q1")].iter().cloned().collect(),
+        );
+        hash.insert(
+            (String::from("q0"), String::from("b")),
+            [String::from("q0")].iter().cloned().collect(),
+        );
+        hash.insert(
+            (String::from("q1"), String::from("a")),
+            [String::from("q2")].iter().cloned().collect(),
+        );
+        hash.insert(
+            (String::from("q3"), String::from("a")),
+            [String::from("q2")].iter().cloned().collect(),
+        );
+
+        let automata = NondeterministicFiniteAutomata {
+            states: ["q0", "q1", "q2", "q3"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+            alphabet: ["a", "b", "&"].iter().map(|x| x.to_string()).collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q2")].iter().cloned().collect(),
+        };
+
+        let determinized = DeterministicFiniteAutomata::from(&automata);
+
+        // only the subsets reachable from the start closure, with the empty one as the sink,
+        // and epsilon is no letter of the result
+        assert_eq!(
+            determinized.states,
+            ["(q0, q1)", "(q2)", "()"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect()
+        );
+        assert_eq!(determinized.alphabet, ab());
+        assert_eq!(determinized.start_state, "(q0, q1)");
+        assert_eq!(
+            determinized.accept_states,
+            [String::from("(q2)")].iter().cloned().collect()
+        );
+
+        let simulator = automata.simulator().unwrap();
+        for word in words(&["a", "b"], 5) {
+            assert_eq!(determinized.compute(&word), simulator.compute(&word));
+        }
+    }
 }
@@ -0,0 +1,272 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+#[derive(Default)]
+struct DictionaryState {
+    transitions: BTreeMap<char, usize>,
+    is_final: bool,
+}
+
+type StateSignature = (bool, Vec<(char, usize)>);
+
+// Daciuk et al. incremental construction: only the path of the last word added is left
+// unregistered, everything to its left is already minimal
+struct DictionaryBuilder {
+    nodes: Vec<DictionaryState>,
+    register: HashMap<StateSignature, usize>,
+}
+
+impl DictionaryBuilder {
+    fn signature(&self, state: usize) -> StateSignature {
+        let node = &self.nodes[state];
+        (
+            node.is_final,
+            node.transitions
+                .iter()
+                .map(|(symbol, out_state)| (*symbol, *out_state))
+                .collect(),
+        )
+    }
+
+    fn replace_or_register(&mut self, state: usize) {
+        let (symbol, child) = match self.nodes[state].transitions.iter().next_back() {
+            Some((symbol, child)) => (*symbol, *child),
+            None => return,
+        };
+
+        self.replace_or_register(child);
+
+        let signature = self.signature(child);
+
+        match self.register.get(&signature) {
+            Some(equivalent) => {
+                self.nodes[state].transitions.insert(symbol, *equivalent);
+            }
+            None => {
+                self.register.insert(signature, child);
+            }
+        }
+    }
+
+    fn add_suffix(&mut self, mut state: usize, suffix: &[char]) {
+        for symbol in suffix {
+            self.nodes.push(DictionaryState::default());
+            let next_state = self.nodes.len() - 1;
+            self.nodes[state].transitions.insert(*symbol, next_state);
+            state = next_state;
+        }
+
+        self.nodes[state].is_final = true;
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn from_sorted_words<I, S>(words: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut builder = DictionaryBuilder {
+            nodes: vec![DictionaryState::default()],
+            register: HashMap::new(),
+        };
+
+        let mut alphabet = BTreeSet::new();
+        let mut previous_word: Option<String> = None;
+
+        for word in words {
+            let word = word.as_ref();
+
+            if let Some(previous_word) = &previous_word {
+                if word < previous_word.as_str() {
+                    return None;
+                }
+                if word == previous_word {
+                    continue;
+                }
+            }
+
+            let symbols: Vec<char> = word.chars().collect();
+
+            let mut state = 0;
+            let mut prefix_length = 0;
+
+            while let Some(next_state) = symbols
+                .get(prefix_length)
+                .and_then(|symbol| builder.nodes[state].transitions.get(symbol))
+            {
+                state = *next_state;
+                prefix_length += 1;
+            }
+
+            builder.replace_or_register(state);
+            builder.add_suffix(state, &symbols[prefix_length..]);
+
+            for symbol in &symbols {
+                alphabet.insert(symbol.to_string());
+            }
+
+            previous_word = Some(word.to_string());
+        }
+
+        builder.replace_or_register(0);
+
+        // name the surviving states in breadth first order, replaced ones are unreachable
+        let mut names = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        names.insert(0, String::from("q0"));
+        queue.push_back(0);
+
+        let mut transition_function = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        while let Some(state) = queue.pop_front() {
+            let name = names[&state].clone();
+
+            if builder.nodes[state].is_final {
+                accept_states.insert(name.clone());
+            }
+
+            for (symbol, out_state) in &builder.nodes[state].transitions {
+                if !names.contains_key(out_state) {
+                    names.insert(*out_state, String::from("q") + &names.len().to_string());
+                    queue.push_back(*out_state);
+                }
+
+                transition_function
+                    .insert((name.clone(), symbol.to_string()), names[out_state].clone());
+            }
+        }
+
+        Some(DeterministicFiniteAutomata {
+            states: names.values().cloned().collect(),
+            alphabet,
+            transition_function,
+            start_state: String::from("q0"),
+            accept_states,
+        })
+    }
+
+    // None when the language is infinite
+    pub fn accepted_words(&self) -> Option<Vec<String>> {
+        let mut productive = self.accept_states.clone();
+
+        let mut stop = false;
+        while !stop {
+            stop = true;
+            for ((state, _), out_state) in &self.transition_function {
+                if productive.contains(out_state) && !productive.contains(state) {
+                    productive.insert(state.clone());
+                    stop = false;
+                }
+            }
+        }
+
+        let mut words = vec![];
+
+        if productive.contains(&self.start_state) {
+            let mut path = BTreeSet::new();
+            self.collect_words(
+                &self.start_state,
+                &productive,
+                &mut path,
+                &mut String::new(),
+                &mut words,
+            )?;
+        }
+
+        Some(words)
+    }
+
+    fn collect_words(
+        &self,
+        state: &str,
+        productive: &BTreeSet<String>,
+        path: &mut BTreeSet<String>,
+        prefix: &mut String,
+        words: &mut Vec<String>,
+    ) -> Option<()> {
+        if self.accept_states.contains(state) {
+            words.push(prefix.clone());
+        }
+
+        path.insert(state.to_string());
+
+        for letter in &self.alphabet {
+            if let Some(out_state) = self
+                .transition_function
+                .get(&(state.to_string(), letter.clone()))
+            {
+                if !productive.contains(out_state) {
+                    continue;
+                }
+                if path.contains(out_state) {
+                    return None;
+                }
+
+                let length = prefix.len();
+                prefix.push_str(letter);
+                self.collect_words(out_state, productive, path, prefix, words)?;
+                prefix.truncate(length);
+            }
+        }
+
+        path.remove(state);
+
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_sorted_words() {
+        let words = [
+            "", "tap", "taps", "tax", "taxes", "tip", "tips", "top", "tops", "tops",
+        ];
+
+        let automata = DeterministicFiniteAutomata::from_sorted_words(words.iter()).unwrap();
+
+        for word in &words {
+            assert!(automata.compute(word));
+        }
+        assert!(!automata.compute("ta"));
+        assert!(!automata.compute("tape"));
+
+        // every state is its own Myhill-Nerode class, plus the one for the missing transitions
+        assert_eq!(automata.states.len(), 8);
+        assert_eq!(
+            automata.myhill_nerode_report().classes.len(),
+            automata.states.len() + 1
+        );
+
+        let mut expected: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+        expected.dedup();
+
+        assert_eq!(automata.accepted_words(), Some(expected));
+    }
+
+    #[test]
+    fn from_unsorted_words() {
+        assert!(DeterministicFiniteAutomata::from_sorted_words(["b", "a"]).is_none());
+    }
+
+    #[test]
+    fn accepted_words_infinite_language() {
+        let mut automata = DeterministicFiniteAutomata::from_sorted_words(["ab"]).unwrap();
+
+        assert_eq!(automata.accepted_words(), Some(vec![String::from("ab")]));
+
+        automata
+            .transition_function
+            .insert((String::from("q2"), String::from("a")), String::from("q1"));
+
+        assert_eq!(automata.accepted_words(), None);
+    }
+}
@@ -0,0 +1,76 @@
+// helpers shared by the tests of the automata modules
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+pub fn set(elements: &[&str]) -> BTreeSet<String> {
+    elements.iter().map(|x| x.to_string()).collect()
+}
+
+pub fn ab() -> BTreeSet<String> {
+    set(&["a", "b"])
+}
+
+// every word over the alphabet up to the given length, shortest first
+pub fn words(alphabet: &[&str], max_length: usize) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut last_layer = vec![String::new()];
+
+    for _ in 0..max_length {
+        last_layer = last_layer
+            .iter()
+            .flat_map(|word| alphabet.iter().map(move |letter| word.clone() + letter))
+            .collect();
+        words.extend(last_layer.iter().cloned());
+    }
+
+    words
+}
+
+pub fn make_automata_ends_with_ab() -> DeterministicFiniteAutomata {
+    let mut hash = BTreeMap::new();
+
+    hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+    hash.insert((String::from("q0"), String::from("b")), String::from("q0"));
+    hash.insert((String::from("q1"), String::from("a")), String::from("q1"));
+    hash.insert((String::from("q1"), String::from("b")), String::from("q2"));
+    hash.insert((String::from("q2"), String::from("a")), String::from("q1"));
+    hash.insert((String::from("q2"), String::from("b")), String::from("q0"));
+
+    DeterministicFiniteAutomata {
+        states: ["q0".to_string(), "q1".to_string(), "q2".to_string()]
+            .iter()
+            .cloned()
+            .collect(),
+        alphabet: ab(),
+        transition_function: hash,
+        start_state: String::from("q0"),
+        accept_states: [String::from("q2")].iter().cloned().collect(),
+    }
+}
+
+// the same automata with every state name behind the prefix
+pub fn renamed(
+    automata: &NondeterministicFiniteAutomata,
+    prefix: &str,
+) -> NondeterministicFiniteAutomata {
+    let rename = |state: &String| String::from(prefix) + state;
+
+    NondeterministicFiniteAutomata {
+        states: automata.states.iter().map(rename).collect(),
+        alphabet: automata.alphabet.clone(),
+        transition_function: automata
+            .transition_function
+            .iter()
+            .map(|((state, letter), targets)| {
+                (
+                    (rename(state), letter.clone()),
+                    targets.iter().map(rename).collect(),
+                )
+            })
+            .collect(),
+        start_state: rename(&automata.start_state),
+        accept_states: automata.accept_states.iter().map(rename).collect(),
+    }
+}
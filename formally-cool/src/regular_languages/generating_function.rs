@@ -0,0 +1,640 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// collatz-wielandt bounds are refined until they are this close
+const GROWTH_RATE_TOLERANCE: f64 = 1e-12;
+const GROWTH_RATE_MAX_ITERATIONS: usize = 100_000;
+
+// integer coefficients, lowest degree first and without trailing zeros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    pub coefficients: Vec<BigInt>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<BigInt>) -> Self {
+        while coefficients.last().is_some_and(Zero::is_zero) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    pub fn constant(value: i64) -> Self {
+        Polynomial::new(vec![BigInt::from(value)])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // the zero polynomial has no degree
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn coefficient(&self, power: usize) -> BigInt {
+        self.coefficients
+            .get(power)
+            .cloned()
+            .unwrap_or_else(BigInt::zero)
+    }
+
+    fn leading(&self) -> BigInt {
+        self.coefficients
+            .last()
+            .cloned()
+            .unwrap_or_else(BigInt::zero)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let length = self.coefficients.len().max(other.coefficients.len());
+        Polynomial::new(
+            (0..length)
+                .map(|power| self.coefficient(power) + other.coefficient(power))
+                .collect(),
+        )
+    }
+
+    fn scale(&self, factor: &BigInt) -> Self {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .map(|coefficient| coefficient * factor)
+                .collect(),
+        )
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self.add(&other.scale(&-BigInt::one()))
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::new(vec![]);
+        }
+
+        let mut coefficients =
+            vec![BigInt::zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, left) in self.coefficients.iter().enumerate() {
+            for (j, right) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] += left * right;
+            }
+        }
+        Polynomial::new(coefficients)
+    }
+
+    // long division that only succeeds when every quotient coefficient is an integer and
+    // nothing remains
+    fn divide_exact(&self, divisor: &Self) -> Option<Self> {
+        let divisor_degree = divisor.degree()?;
+        let leading = divisor.leading();
+
+        let mut remainder = self.coefficients.clone();
+        let quotient_length = (remainder.len() + 1).saturating_sub(divisor.coefficients.len());
+        let mut quotient = vec![BigInt::zero(); quotient_length];
+
+        for power in (0..quotient_length).rev() {
+            let (top, rest) = remainder[power + divisor_degree].div_rem(&leading);
+            if !rest.is_zero() {
+                return None;
+            }
+            for (i, coefficient) in divisor.coefficients.iter().enumerate() {
+                remainder[power + i] -= &top * coefficient;
+            }
+            quotient[power] = top;
+        }
+
+        if remainder.iter().all(Zero::is_zero) {
+            Some(Polynomial::new(quotient))
+        } else {
+            None
+        }
+    }
+
+    // every coefficient divided by a constant known to divide them all
+    fn divide_constant(&self, divisor: &BigInt) -> Self {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .map(|coefficient| coefficient / divisor)
+                .collect(),
+        )
+    }
+
+    fn content(&self) -> BigInt {
+        self.coefficients
+            .iter()
+            .fold(BigInt::zero(), |content, coefficient| {
+                content.gcd(coefficient)
+            })
+    }
+
+    fn primitive_part(&self) -> Self {
+        let content = self.content();
+        if content.is_zero() {
+            self.clone()
+        } else {
+            self.divide_constant(&content)
+        }
+    }
+
+    // remainder of the leading coefficient of divisor to the power of the degree difference
+    // plus one times self, which is the smallest multiple of self that divides in the integers
+    fn pseudo_remainder(&self, divisor: &Self) -> Self {
+        let divisor_degree = divisor.degree().unwrap();
+        let leading = divisor.leading();
+        let mut remainder = self.clone();
+        let mut steps = (self.coefficients.len() + 1).saturating_sub(divisor.coefficients.len());
+
+        while let Some(degree) = remainder.degree() {
+            if degree < divisor_degree {
+                break;
+            }
+
+            let mut shifted = vec![BigInt::zero(); degree - divisor_degree];
+            shifted.extend(divisor.scale(&remainder.leading()).coefficients);
+
+            remainder = remainder.scale(&leading).sub(&Polynomial::new(shifted));
+            steps -= 1;
+        }
+
+        remainder.scale(&leading.pow(steps as u32))
+    }
+
+    // subresultant remainder sequence: each pseudo remainder is divided by the factor it is
+    // known to carry, so the coefficients stay the size of minors of the sylvester matrix
+    fn gcd(&self, other: &Self) -> Self {
+        let (mut left, mut right) = if self.degree() >= other.degree() {
+            (self.primitive_part(), other.primitive_part())
+        } else {
+            (other.primitive_part(), self.primitive_part())
+        };
+        let mut leading = BigInt::one();
+        let mut scale = BigInt::one();
+
+        while let (Some(left_degree), Some(right_degree)) = (left.degree(), right.degree()) {
+            let delta = (left_degree - right_degree) as u32;
+            let remainder = left.pseudo_remainder(&right);
+
+            left = right;
+            right = remainder.divide_constant(&(&leading * scale.pow(delta)));
+
+            leading = left.leading();
+            if delta > 0 {
+                scale = leading.pow(delta) / scale.pow(delta - 1);
+            }
+        }
+
+        // the sign is free, the leading coefficient is made positive
+        let mut content = self.content().gcd(&other.content());
+        if left.leading().is_negative() {
+            content = -content;
+        }
+        left.primitive_part().scale(&content)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        let mut first = true;
+        for (power, coefficient) in self.coefficients.iter().enumerate() {
+            if coefficient.is_zero() {
+                continue;
+            }
+
+            let sign = if coefficient.is_negative() { "-" } else { "+" };
+            match (first, sign) {
+                (true, "-") => write!(f, "-")?,
+                (true, _) => (),
+                (false, _) => write!(f, " {} ", sign)?,
+            }
+            first = false;
+
+            let magnitude = coefficient.abs();
+            if !magnitude.is_one() || power == 0 {
+                write!(f, "{}", magnitude)?;
+            }
+            match power {
+                0 => (),
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{}", power)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// sum over n of the number of accepted words of length n times x^n, in lowest terms with a
+// denominator whose constant term is 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratingFunction {
+    pub numerator: Polynomial,
+    pub denominator: Polynomial,
+}
+
+impl GeneratingFunction {
+    // the first terms of the power series, that is the number of accepted words of each length
+    pub fn series(&self, terms: usize) -> Vec<BigInt> {
+        let mut series: Vec<BigInt> = vec![];
+
+        for n in 0..terms {
+            let mut term = self.numerator.coefficient(n);
+            for k in 1..=n {
+                term -= self.denominator.coefficient(k) * &series[n - k];
+            }
+            series.push(term / self.denominator.coefficient(0));
+        }
+
+        series
+    }
+}
+
+impl fmt::Display for GeneratingFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let terms = self
+            .numerator
+            .coefficients
+            .iter()
+            .filter(|coefficient| !coefficient.is_zero())
+            .count();
+
+        if self.denominator == Polynomial::constant(1) {
+            write!(f, "{}", self.numerator)
+        } else if terms > 1 {
+            write!(f, "({}) / ({})", self.numerator, self.denominator)
+        } else {
+            write!(f, "{} / ({})", self.numerator, self.denominator)
+        }
+    }
+}
+
+// fraction free gaussian elimination; every division is exact and the last entry ends up
+// being the determinant, provided the leading principal minors before it are not zero
+fn bareiss_determinant(mut matrix: Vec<Vec<Polynomial>>) -> Polynomial {
+    let size = matrix.len();
+    let mut previous = Polynomial::constant(1);
+
+    for k in 0..size - 1 {
+        for i in k + 1..size {
+            for j in k + 1..size {
+                let entry = matrix[i][j]
+                    .mul(&matrix[k][k])
+                    .sub(&matrix[i][k].mul(&matrix[k][j]));
+                matrix[i][j] = entry.divide_exact(&previous).unwrap();
+            }
+        }
+        previous = matrix[k][k].clone();
+    }
+
+    matrix[size - 1][size - 1].clone()
+}
+
+// spectral radius of a non negative irreducible matrix: power iteration on I + matrix, which
+// is primitive, squeezing the radius between the smallest and largest componentwise ratio
+fn perron_root(matrix: &[Vec<f64>]) -> f64 {
+    let size = matrix.len();
+    let mut vector = vec![1.0; size];
+    let mut bounds = (0.0, f64::INFINITY);
+
+    for _ in 0..GROWTH_RATE_MAX_ITERATIONS {
+        let next: Vec<f64> = (0..size)
+            .map(|i| vector[i] + (0..size).map(|j| matrix[i][j] * vector[j]).sum::<f64>())
+            .collect();
+
+        let ratios = (0..size).map(|i| next[i] / vector[i] - 1.0);
+        bounds = (
+            ratios.clone().fold(f64::INFINITY, f64::min),
+            ratios.fold(0.0, f64::max),
+        );
+        if bounds.1 - bounds.0 < GROWTH_RATE_TOLERANCE {
+            break;
+        }
+
+        let norm = next.iter().cloned().fold(0.0, f64::max);
+        vector = next.iter().map(|entry| entry / norm).collect();
+    }
+
+    (bounds.0 + bounds.1) / 2.0
+}
+
+impl DeterministicFiniteAutomata {
+    // transfer matrix of the useful states: entry (p, q) counts the letters leading from p to q
+    fn transfer_matrix(&self) -> Option<(usize, Vec<Vec<i128>>, Vec<bool>)> {
+        let automata = self
+            .remove_unreachable_states()
+            .remove_non_productive_states();
+
+        if !automata.states.contains(&automata.start_state) {
+            return None;
+        }
+
+        let index: BTreeMap<&String, usize> = automata
+            .states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state, i))
+            .collect();
+
+        let mut matrix = vec![vec![0; index.len()]; index.len()];
+        for ((state, letter), out_state) in &automata.transition_function {
+            if automata.alphabet.contains(letter) {
+                matrix[index[state]][index[out_state]] += 1;
+            }
+        }
+
+        let accepting = automata
+            .states
+            .iter()
+            .map(|state| automata.accept_states.contains(state))
+            .collect();
+
+        Some((index[&automata.start_state], matrix, accepting))
+    }
+
+    // the series is the start entry of the solution y of (I - xA) y = accepting, which cramer's
+    // rule gives as a ratio of two determinants
+    pub fn generating_function(&self) -> GeneratingFunction {
+        let (start, matrix, accepting) = match self.transfer_matrix() {
+            Some(transfer_matrix) => transfer_matrix,
+            None => {
+                return GeneratingFunction {
+                    numerator: Polynomial::new(vec![]),
+                    denominator: Polynomial::constant(1),
+                }
+            }
+        };
+
+        // the start state goes last, so that replacing its column keeps the pivots bareiss
+        // divides by equal to leading principal minors of I - xA, which never vanish at x = 0
+        let size = matrix.len();
+        let order: Vec<usize> = (0..size)
+            .filter(|state| *state != start)
+            .chain(Some(start))
+            .collect();
+
+        let system: Vec<Vec<Polynomial>> = order
+            .iter()
+            .map(|i| {
+                order
+                    .iter()
+                    .map(|j| {
+                        Polynomial::new(vec![
+                            BigInt::from((i == j) as i64),
+                            BigInt::from(-matrix[*i][*j]),
+                        ])
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut replaced = system.clone();
+        for (row, state) in order.iter().enumerate() {
+            replaced[row][size - 1] = Polynomial::constant(accepting[*state] as i64);
+        }
+
+        let numerator = bareiss_determinant(replaced);
+        let denominator = bareiss_determinant(system);
+
+        let common = numerator.gcd(&denominator);
+        let mut numerator = numerator.divide_exact(&common).unwrap();
+        let mut denominator = denominator.divide_exact(&common).unwrap();
+
+        if denominator.coefficient(0).is_negative() {
+            numerator = numerator.scale(&-BigInt::one());
+            denominator = denominator.scale(&-BigInt::one());
+        }
+
+        GeneratingFunction {
+            numerator,
+            denominator,
+        }
+    }
+
+    // limit of the n-th root of the number of accepted words of length n: 0 for finite
+    // languages, 1 for polynomially many words and the largest spectral radius among the
+    // strongly connected components of the useful states otherwise
+    pub fn growth_rate(&self) -> f64 {
+        let (_, matrix, _) = match self.transfer_matrix() {
+            Some(transfer_matrix) => transfer_matrix,
+            None => return 0.0,
+        };
+        let size = matrix.len();
+
+        let mut reaches: Vec<Vec<bool>> = (0..size)
+            .map(|i| (0..size).map(|j| matrix[i][j] > 0).collect())
+            .collect();
+        for k in 0..size {
+            for i in 0..size {
+                for j in 0..size {
+                    if reaches[i][k] && reaches[k][j] {
+                        reaches[i][j] = true;
+                    }
+                }
+            }
+        }
+
+        let mut assigned = vec![false; size];
+        let mut growth_rate: f64 = 0.0;
+
+        for state in 0..size {
+            if assigned[state] || !reaches[state][state] {
+                continue;
+            }
+
+            let component: Vec<usize> = (0..size)
+                .filter(|other| reaches[state][*other] && reaches[*other][state])
+                .collect();
+            for other in &component {
+                assigned[*other] = true;
+            }
+
+            let block: Vec<Vec<f64>> = component
+                .iter()
+                .map(|i| component.iter().map(|j| matrix[*i][*j] as f64).collect())
+                .collect();
+            growth_rate = growth_rate.max(perron_root(&block));
+        }
+
+        growth_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::{ab, set};
+
+    fn polynomial(coefficients: &[i64]) -> Polynomial {
+        Polynomial::new(coefficients.iter().cloned().map(BigInt::from).collect())
+    }
+
+    fn integers(values: &[i64]) -> Vec<BigInt> {
+        values.iter().cloned().map(BigInt::from).collect()
+    }
+
+    // the number of accepted words of each length, by pushing the count of words ending in
+    // each state through the transitions one letter at a time
+    fn count_words(automata: &DeterministicFiniteAutomata, max_length: usize) -> Vec<BigInt> {
+        let mut ending: BTreeMap<String, BigInt> = BTreeMap::new();
+        ending.insert(automata.start_state.clone(), BigInt::one());
+        let mut counts = vec![];
+
+        for _ in 0..=max_length {
+            counts.push(
+                ending
+                    .iter()
+                    .filter(|(state, _)| automata.accept_states.contains(*state))
+                    .map(|(_, count)| count)
+                    .sum(),
+            );
+
+            let mut next: BTreeMap<String, BigInt> = BTreeMap::new();
+            for (state, count) in &ending {
+                for letter in &automata.alphabet {
+                    if let Some(target) = automata
+                        .transition_function
+                        .get(&(state.clone(), letter.clone()))
+                    {
+                        *next.entry(target.clone()).or_insert_with(BigInt::zero) += count;
+                    }
+                }
+            }
+            ending = next;
+        }
+
+        counts
+    }
+
+    #[test]
+    fn polynomial_arithmetic() {
+        let left = polynomial(&[1, -1]);
+        let right = polynomial(&[1, 1, 0]);
+
+        assert_eq!(right.degree(), Some(1));
+        let product = left.mul(&right);
+        assert_eq!(product, polynomial(&[1, 0, -1]));
+        assert_eq!(product.divide_exact(&left), Some(right.clone()));
+        assert_eq!(right.divide_exact(&left), None);
+
+        let common = polynomial(&[2, -3, 1]);
+        let left = left.mul(&common).scale(&BigInt::from(6));
+        let right = right.mul(&common).scale(&BigInt::from(4));
+        assert_eq!(left.gcd(&right), common.scale(&BigInt::from(2)));
+
+        // x^2 + 1 and x + 1 only share constants
+        assert_eq!(
+            polynomial(&[1, 0, 1]).gcd(&polynomial(&[3, 3])),
+            Polynomial::constant(1)
+        );
+
+        let huge = polynomial(&[1, i64::MAX]);
+        assert_eq!(
+            huge.mul(&huge).coefficient(2),
+            BigInt::from(i64::MAX) * BigInt::from(i64::MAX)
+        );
+
+        assert_eq!(polynomial(&[1, -2, 0, 3]).to_string(), "1 - 2x + 3x^3");
+        assert_eq!(polynomial(&[0, -1, 1]).to_string(), "-x + x^2");
+    }
+
+    #[test]
+    fn generating_function() {
+        let sigma_star = catalog::sigma_star(&ab()).generating_function();
+        assert_eq!(sigma_star.to_string(), "1 / (1 - 2x)");
+        assert_eq!(sigma_star.series(5), integers(&[1, 2, 4, 8, 16]));
+        assert_eq!(sigma_star.series(201)[200], BigInt::one() << 200);
+
+        let finite = DeterministicFiniteAutomata::from_sorted_words(["a", "ab", "b", "bab"])
+            .unwrap()
+            .generating_function();
+        assert_eq!(finite.to_string(), "2x + x^2 + x^3");
+        assert_eq!(finite.series(5), integers(&[0, 2, 1, 1, 0]));
+
+        let even_length = catalog::length_mod(2, 0, &ab())
+            .unwrap()
+            .generating_function();
+        assert_eq!(even_length.to_string(), "1 / (1 - 4x^2)");
+        assert_eq!(even_length.series(5), integers(&[1, 0, 4, 0, 16]));
+
+        let empty = DeterministicFiniteAutomata::from_sorted_words(Vec::<String>::new())
+            .unwrap()
+            .generating_function();
+        assert_eq!(empty.to_string(), "0");
+    }
+
+    #[test]
+    fn series_counts_words() {
+        let no_aa = catalog::contains("aa", &ab()).complement();
+        let function = no_aa.generating_function();
+
+        assert_eq!(function.to_string(), "(1 + x) / (1 - x - x^2)");
+        assert_eq!(function.series(11), count_words(&no_aa, 10));
+
+        let automata = catalog::contains("abb", &ab())
+            .intersection(&catalog::length_mod(3, 1, &ab()).unwrap());
+        assert_eq!(
+            automata.generating_function().series(11),
+            count_words(&automata, 10)
+        );
+
+        // the remainder sequences of these outgrew machine integers long before the results do
+        let letters = set(&["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"]);
+        let automata =
+            catalog::contains("abc", &letters).intersection(&catalog::contains("ba", &letters));
+        let function = automata.generating_function();
+        assert_eq!(
+            function.to_string(),
+            "(x^4 - 18x^5 + 80x^6 + x^7 + x^8 - x^9) / (1 - 40x + 602x^2 - 4058x^3 + 10540x^4 - 1387x^5 - 2260x^6 + 1300x^7 + 11x^8 - 111x^9 + 10x^10)"
+        );
+        assert_eq!(function.series(61), count_words(&automata, 60));
+        assert_eq!(
+            function.numerator.gcd(&function.denominator),
+            Polynomial::constant(1)
+        );
+
+        let abc = set(&["a", "b", "c"]);
+        let automata = catalog::contains("abcab", &abc)
+            .intersection(&catalog::length_mod(4, 1, &abc).unwrap());
+        let function = automata.generating_function();
+        assert_eq!(
+            function.to_string(),
+            "(x^5 + 237x^9 - 623x^13 + 828x^17 - 246x^21) / (1 - 162x^4 + 6665x^8 - 8467x^12 + 3542x^16 - 4780x^20 + 81x^24)"
+        );
+        assert_eq!(function.series(61), count_words(&automata, 60));
+        assert_eq!(
+            function.numerator.gcd(&function.denominator),
+            Polynomial::constant(1)
+        );
+    }
+
+    #[test]
+    fn growth_rate() {
+        let close = |left: f64, right: f64| (left - right).abs() < 1e-9;
+
+        assert!(close(catalog::sigma_star(&ab()).growth_rate(), 2.0));
+        assert!(close(
+            catalog::contains("aa", &ab()).complement().growth_rate(),
+            (1.0 + 5f64.sqrt()) / 2.0
+        ));
+        assert!(close(
+            catalog::exactly_n_occurrences("a", 2, &ab()).growth_rate(),
+            1.0
+        ));
+        assert!(close(
+            DeterministicFiniteAutomata::from_sorted_words(["a", "ab"])
+                .unwrap()
+                .growth_rate(),
+            0.0
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+fn position_state(position: usize, errors: usize) -> String {
+    String::from("(") + &position.to_string() + ", " + &errors.to_string() + ")"
+}
+
+// halfway through swapping the letters at position and position + 1
+fn transposition_state(position: usize, errors: usize) -> String {
+    String::from("(") + &position.to_string() + ", " + &errors.to_string() + ", t)"
+}
+
+// state (i, e) has read a prefix of the input that is e edits away from the first i letters of
+// word: letters are matched or substituted, inserted letters stay in place and deletions are
+// epsilon moves
+fn edit_distance_automaton(
+    word: &str,
+    max_distance: usize,
+    alphabet: &BTreeSet<String>,
+    transpositions: bool,
+) -> NondeterministicFiniteAutomata {
+    let symbols: Vec<String> = word.chars().map(|symbol| symbol.to_string()).collect();
+    let alphabet: BTreeSet<String> = alphabet.iter().chain(symbols.iter()).cloned().collect();
+
+    let mut states = BTreeSet::new();
+    let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+    let mut accept_states = BTreeSet::new();
+
+    let mut add_transition = |from: String, letter: &String, to: String| {
+        transition_function
+            .entry((from, letter.clone()))
+            .or_default()
+            .insert(to);
+    };
+
+    for position in 0..=symbols.len() {
+        for errors in 0..=max_distance {
+            let state = position_state(position, errors);
+            states.insert(state.clone());
+
+            if position == symbols.len() {
+                accept_states.insert(state.clone());
+            } else {
+                add_transition(
+                    state.clone(),
+                    &symbols[position],
+                    position_state(position + 1, errors),
+                );
+            }
+
+            if errors == max_distance {
+                continue;
+            }
+
+            for letter in &alphabet {
+                add_transition(state.clone(), letter, position_state(position, errors + 1));
+
+                if position < symbols.len() && *letter != symbols[position] {
+                    add_transition(
+                        state.clone(),
+                        letter,
+                        position_state(position + 1, errors + 1),
+                    );
+                }
+            }
+
+            if position < symbols.len() {
+                add_transition(
+                    state.clone(),
+                    &String::from("&"),
+                    position_state(position + 1, errors + 1),
+                );
+            }
+
+            if transpositions
+                && position + 1 < symbols.len()
+                && symbols[position] != symbols[position + 1]
+            {
+                let halfway = transposition_state(position, errors);
+                states.insert(halfway.clone());
+
+                add_transition(state.clone(), &symbols[position + 1], halfway.clone());
+                add_transition(
+                    halfway,
+                    &symbols[position],
+                    position_state(position + 2, errors + 1),
+                );
+            }
+        }
+    }
+
+    NondeterministicFiniteAutomata {
+        states,
+        alphabet,
+        transition_function,
+        start_state: position_state(0, 0),
+        accept_states,
+    }
+}
+
+impl NondeterministicFiniteAutomata {
+    // every string over the alphabet (extended with the letters of word) within max_distance
+    // insertions, deletions and substitutions of word
+    pub fn levenshtein_automaton(
+        word: &str,
+        max_distance: usize,
+        alphabet: &BTreeSet<String>,
+    ) -> Self {
+        edit_distance_automaton(word, max_distance, alphabet, false)
+    }
+
+    // same as levenshtein_automaton, but swapping two adjacent letters costs a single edit
+    pub fn damerau_levenshtein_automaton(
+        word: &str,
+        max_distance: usize,
+        alphabet: &BTreeSet<String>,
+    ) -> Self {
+        edit_distance_automaton(word, max_distance, alphabet, true)
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn levenshtein_automaton(
+        word: &str,
+        max_distance: usize,
+        alphabet: &BTreeSet<String>,
+    ) -> Self {
+        NondeterministicFiniteAutomata::levenshtein_automaton(word, max_distance, alphabet)
+            .determinize()
+    }
+
+    pub fn damerau_levenshtein_automaton(
+        word: &str,
+        max_distance: usize,
+        alphabet: &BTreeSet<String>,
+    ) -> Self {
+        NondeterministicFiniteAutomata::damerau_levenshtein_automaton(word, max_distance, alphabet)
+            .determinize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::words;
+
+    fn edit_distance(left: &str, right: &str, transpositions: bool) -> usize {
+        let left: Vec<char> = left.chars().collect();
+        let right: Vec<char> = right.chars().collect();
+
+        let mut distance = vec![vec![0; right.len() + 1]; left.len() + 1];
+
+        for i in 0..=left.len() {
+            for j in 0..=right.len() {
+                distance[i][j] = if i == 0 || j == 0 {
+                    i + j
+                } else {
+                    let substitution = if left[i - 1] == right[j - 1] { 0 } else { 1 };
+                    (distance[i - 1][j] + 1)
+                        .min(distance[i][j - 1] + 1)
+                        .min(distance[i - 1][j - 1] + substitution)
+                };
+
+                if transpositions
+                    && i > 1
+                    && j > 1
+                    && left[i - 1] == right[j - 2]
+                    && left[i - 2] == right[j - 1]
+                {
+                    distance[i][j] = distance[i][j].min(distance[i - 2][j - 2] + 1);
+                }
+            }
+        }
+
+        distance[left.len()][right.len()]
+    }
+
+    #[test]
+    fn levenshtein_automaton() {
+        let alphabet = ["a", "b", "c"].iter().map(|x| x.to_string()).collect();
+
+        for transpositions in &[false, true] {
+            let (nfa, dfa) = if *transpositions {
+                (
+                    NondeterministicFiniteAutomata::damerau_levenshtein_automaton(
+                        "abca", 2, &alphabet,
+                    ),
+                    DeterministicFiniteAutomata::damerau_levenshtein_automaton(
+                        "abca", 2, &alphabet,
+                    ),
+                )
+            } else {
+                (
+                    NondeterministicFiniteAutomata::levenshtein_automaton("abca", 2, &alphabet),
+                    DeterministicFiniteAutomata::levenshtein_automaton("abca", 2, &alphabet),
+                )
+            };
+
+            assert_eq!(nfa.start_state, "(0, 0)");
+
+            for word in words(&["a", "b", "c"], 6) {
+                assert_eq!(
+                    dfa.compute(&word),
+                    edit_distance("abca", &word, *transpositions) <= 2
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn transposition() {
+        let alphabet = BTreeSet::new();
+
+        let levenshtein = DeterministicFiniteAutomata::levenshtein_automaton("form", 1, &alphabet);
+        let damerau =
+            DeterministicFiniteAutomata::damerau_levenshtein_automaton("form", 1, &alphabet);
+
+        assert!(!levenshtein.compute("from"));
+        assert!(damerau.compute("from"));
+        assert!(levenshtein.compute("fom"));
+        assert!(damerau.compute("fom"));
+    }
+
+    #[test]
+    fn intersection_with_dictionary() {
+        let dictionary = DeterministicFiniteAutomata::from_sorted_words([
+            "cart", "cast", "cat", "cats", "coat", "cut", "dog", "scat",
+        ])
+        .unwrap();
+
+        let fuzzy =
+            DeterministicFiniteAutomata::levenshtein_automaton("cat", 1, &dictionary.alphabet);
+
+        assert_eq!(
+            fuzzy.intersection(&dictionary).accepted_words(),
+            Some(
+                ["cart", "cast", "cat", "cats", "coat", "cut", "scat"]
+                    .iter()
+                    .map(|word| word.to_string())
+                    .collect()
+            )
+        );
+    }
+}
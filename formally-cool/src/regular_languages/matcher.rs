@@ -0,0 +1,413 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::io;
+use std::io::Read;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+// byte offsets into the stream, end is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchPosition {
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct Matcher<'a> {
+    automata: &'a DeterministicFiniteAutomata,
+    productive: BTreeSet<String>,
+    // bytes of a character split across chunks
+    pending: Vec<u8>,
+    // set once the input is not utf-8, until the next reset
+    invalid: Option<std::str::Utf8Error>,
+    offset: usize,
+    // full match: the state after everything fed so far, None once a transition is missing
+    state: Option<String>,
+    // search: characters from the start of the current attempt that are not resolved yet, how
+    // many of them the attempt has read, and how many of them the longest accepted prefix has;
+    // with a window limit an attempt reading that many characters settles with what it has,
+    // which bounds the memory and the rescanning after a failed attempt
+    window: VecDeque<(usize, char)>,
+    window_limit: Option<usize>,
+    attempt_state: Option<String>,
+    attempt_length: usize,
+    accepted_length: Option<usize>,
+}
+
+impl<'a> Matcher<'a> {
+    // the window grows as long as an attempt may still match, which for an automaton like
+    // ab*c over a long run of b is the whole run
+    pub fn new(automata: &'a DeterministicFiniteAutomata) -> Self {
+        let mut productive = automata.accept_states.clone();
+
+        let mut stop = false;
+        while !stop {
+            stop = true;
+            for ((state, _), out_state) in &automata.transition_function {
+                if productive.contains(out_state) && !productive.contains(state) {
+                    productive.insert(state.clone());
+                    stop = false;
+                }
+            }
+        }
+
+        Matcher {
+            automata,
+            productive,
+            pending: vec![],
+            invalid: None,
+            offset: 0,
+            state: Some(automata.start_state.clone()),
+            window: VecDeque::new(),
+            window_limit: None,
+            attempt_state: Some(automata.start_state.clone()),
+            attempt_length: 0,
+            accepted_length: None,
+        }
+    }
+
+    // matches longer than window_limit characters are cut to the longest accepted prefix that
+    // fits, or missed when there is none
+    pub fn with_window_limit(
+        automata: &'a DeterministicFiniteAutomata,
+        window_limit: usize,
+    ) -> Self {
+        Matcher {
+            window_limit: Some(window_limit.max(1)),
+            ..Matcher::new(automata)
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.invalid = None;
+        self.offset = 0;
+        self.state = Some(self.automata.start_state.clone());
+        self.window.clear();
+        self.restart_attempt();
+    }
+
+    // true when everything fed since the last reset is accepted as a whole
+    pub fn is_match(&self) -> bool {
+        match &self.state {
+            Some(state) => self.pending.is_empty() && self.automata.accept_states.contains(state),
+            None => false,
+        }
+    }
+
+    // returns the leftmost-longest non-empty matches that can no longer grow; the ones that
+    // still might are reported by a later feed or by finish. a chunk that is not utf-8 is an
+    // error that ends the input: nothing of it is searched, and this and every other call fails
+    // until the next reset
+    pub fn feed(&mut self, chunk: &[u8]) -> io::Result<Vec<MatchPosition>> {
+        self.check_valid()?;
+        self.pending.extend_from_slice(chunk);
+
+        let valid_length = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(error) => match error.error_len() {
+                Some(_) => {
+                    self.invalid = Some(error);
+                    self.pending.clear();
+                    self.state = None;
+                    return self.check_valid().map(|_| vec![]);
+                }
+                None => error.valid_up_to(),
+            },
+        };
+
+        let rest = self.pending.split_off(valid_length);
+        let text = String::from_utf8(std::mem::replace(&mut self.pending, rest)).unwrap();
+
+        let mut matches = vec![];
+
+        for symbol in text.chars() {
+            let letter = symbol.to_string();
+
+            self.state = self.state.take().and_then(|state| {
+                self.automata
+                    .transition_function
+                    .get(&(state, letter.clone()))
+                    .cloned()
+            });
+
+            self.window.push_back((self.offset, symbol));
+            self.offset += symbol.len_utf8();
+
+            self.advance(&mut matches);
+        }
+
+        Ok(matches)
+    }
+
+    // end of input: attempts waiting for more characters are settled with what they have
+    pub fn finish(&mut self) -> io::Result<Vec<MatchPosition>> {
+        self.check_valid()?;
+        if !self.pending.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "stream ends in the middle of a character",
+            ));
+        }
+
+        let mut matches = vec![];
+
+        self.advance(&mut matches);
+        while !self.window.is_empty() {
+            self.settle_attempt(&mut matches);
+            self.advance(&mut matches);
+        }
+
+        Ok(matches)
+    }
+
+    pub fn read_from<R: Read>(&mut self, mut reader: R) -> io::Result<Vec<MatchPosition>> {
+        let mut buffer = vec![0; READ_BUFFER_SIZE];
+        let mut matches = vec![];
+
+        loop {
+            let length = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            matches.extend(self.feed(&buffer[..length])?);
+        }
+
+        matches.extend(self.finish()?);
+
+        Ok(matches)
+    }
+
+    fn check_valid(&self) -> io::Result<()> {
+        match self.invalid {
+            Some(error) => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+            None => Ok(()),
+        }
+    }
+
+    fn restart_attempt(&mut self) {
+        self.attempt_state = Some(self.automata.start_state.clone());
+        self.attempt_length = 0;
+        self.accepted_length = None;
+    }
+
+    // report the longest accepted prefix of the attempt, or give up on its first character
+    fn settle_attempt(&mut self, matches: &mut Vec<MatchPosition>) {
+        match self.accepted_length {
+            Some(length) => {
+                let (start, _) = self.window[0];
+                let (last, symbol) = self.window[length - 1];
+                matches.push(MatchPosition {
+                    start,
+                    end: last + symbol.len_utf8(),
+                });
+                self.window.drain(..length);
+            }
+            None => {
+                self.window.pop_front();
+            }
+        }
+
+        self.restart_attempt();
+    }
+
+    fn advance(&mut self, matches: &mut Vec<MatchPosition>) {
+        loop {
+            let alive = match &self.attempt_state {
+                Some(state) => self.productive.contains(state),
+                None => false,
+            };
+
+            if !alive {
+                if self.window.is_empty() {
+                    self.restart_attempt();
+                    return;
+                }
+                self.settle_attempt(matches);
+                continue;
+            }
+
+            if Some(self.attempt_length) == self.window_limit {
+                self.settle_attempt(matches);
+                continue;
+            }
+
+            if self.attempt_length == self.window.len() {
+                return;
+            }
+
+            let (_, symbol) = self.window[self.attempt_length];
+            self.attempt_length += 1;
+
+            self.attempt_state = self.attempt_state.take().and_then(|state| {
+                self.automata
+                    .transition_function
+                    .get(&(state, symbol.to_string()))
+                    .cloned()
+            });
+
+            if let Some(state) = &self.attempt_state {
+                if self.automata.accept_states.contains(state) {
+                    self.accepted_length = Some(self.attempt_length);
+                }
+            }
+        }
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn matcher(&self) -> Matcher<'_> {
+        Matcher::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    // a followed by any number of b
+    fn make_automata_ab_star() -> DeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q1"));
+
+        DeterministicFiniteAutomata {
+            states: ["q0".to_string(), "q1".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q1")].iter().cloned().collect(),
+        }
+    }
+
+    fn positions(matches: &[MatchPosition]) -> Vec<(usize, usize)> {
+        matches
+            .iter()
+            .map(|position| (position.start, position.end))
+            .collect()
+    }
+
+    #[test]
+    fn search() {
+        let automata = make_automata_ab_star();
+
+        let matches = automata
+            .matcher()
+            .read_from("xabbbaab-b".as_bytes())
+            .unwrap();
+
+        assert_eq!(positions(&matches), vec![(1, 5), (5, 6), (6, 8)]);
+    }
+
+    #[test]
+    fn search_with_lookahead() {
+        // accepts "a" and "abcd", so "abc" has to be buffered until it is known not to grow
+        let automata = DeterministicFiniteAutomata::from_sorted_words(["a", "abcd"]).unwrap();
+
+        let text = "abcxabcdabc";
+        let mut matcher = automata.matcher();
+        let mut matches = vec![];
+
+        for byte in text.as_bytes() {
+            matches.extend(matcher.feed(&[*byte]).unwrap());
+        }
+        assert_eq!(positions(&matches), vec![(0, 1), (4, 8)]);
+
+        matches.extend(matcher.finish().unwrap());
+        assert_eq!(positions(&matches), vec![(0, 1), (4, 8), (8, 9)]);
+    }
+
+    #[test]
+    fn search_without_match() {
+        // a, any number of b and then c, which never comes
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("c")), String::from("q2"));
+
+        let automata = DeterministicFiniteAutomata {
+            states: ["q0".to_string(), "q1".to_string(), "q2".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string(), "c".to_string()]
+                .iter()
+                .cloned()
+                .collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q2")].iter().cloned().collect(),
+        };
+
+        let mut matcher = Matcher::with_window_limit(&automata, 4096);
+        let chunk = vec![b'b'; 64 * 1024];
+
+        assert!(matcher.feed(b"a").unwrap().is_empty());
+        for _ in 0..16 {
+            assert!(matcher.feed(&chunk).unwrap().is_empty());
+            assert!(matcher.window.len() <= 4096);
+        }
+        assert!(matcher.finish().unwrap().is_empty());
+
+        // without a limit the whole run is kept until the c comes
+        let long = String::from("a") + &"b".repeat(10_000) + "c";
+        let matches = automata.matcher().read_from(long.as_bytes()).unwrap();
+
+        assert_eq!(positions(&matches), vec![(0, 10_002)]);
+
+        // a match longer than the window is missed
+        let mut matcher = Matcher::with_window_limit(&automata, 4);
+        let matches = matcher.read_from("abbc-abbbbc".as_bytes()).unwrap();
+
+        assert_eq!(positions(&matches), vec![(0, 4)]);
+    }
+
+    #[test]
+    fn full_match_across_chunks() {
+        let automata = DeterministicFiniteAutomata::from_sorted_words(["café", "cafés"]).unwrap();
+
+        let bytes = "cafés".as_bytes();
+        let mut matcher = automata.matcher();
+
+        matcher.feed(&bytes[..3]).unwrap();
+        assert!(!matcher.is_match());
+
+        // the accented letter is two bytes long and arrives split between chunks
+        matcher.feed(&bytes[3..4]).unwrap();
+        assert!(!matcher.is_match());
+        matcher.feed(&bytes[4..5]).unwrap();
+        assert!(matcher.is_match());
+
+        matcher.feed(&bytes[5..]).unwrap();
+        assert!(matcher.is_match());
+
+        matcher.feed(b"!").unwrap();
+        assert!(!matcher.is_match());
+
+        matcher.reset();
+        let matches = matcher.read_from(io::Cursor::new(bytes)).unwrap();
+        assert!(matcher.is_match());
+        assert_eq!(positions(&matches), vec![(0, 6)]);
+
+        // invalid utf-8 ends the input until the next reset
+        matcher.reset();
+        assert!(matcher.feed(&[0xff]).is_err());
+        assert!(matcher.feed(b"cafe").is_err());
+        assert!(matcher.finish().is_err());
+        assert!(!matcher.is_match());
+        assert!(matcher.pending.is_empty());
+
+        matcher.reset();
+        matcher.feed("café".as_bytes()).unwrap();
+        assert!(matcher.is_match());
+    }
+}
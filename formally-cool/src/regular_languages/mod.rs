@@ -1,7 +1,45 @@
+pub mod catalog;
+
+mod active_learning;
+mod aho_corasick;
+mod alternating_finite_automata;
+mod antichains;
+mod compiled_automata;
+mod conformance_testing;
 mod deterministic_finite_automata;
+mod dictionary_automata;
+#[cfg(test)]
+pub mod fixtures;
+mod generating_function;
+mod levenshtein_automata;
+mod matcher;
+mod myhill_nerode;
+mod nfa_reduction;
+mod nfa_simulation;
 mod nondeterministic_finite_automata;
+mod passive_learning;
+mod pumping_lemma;
 mod regular_grammar;
+mod suffix_automaton;
+mod synchronizing_word;
+mod syntactic_monoid;
+mod two_way_dfa;
+mod weighted_automata;
 
+pub use self::active_learning::*;
+pub use self::aho_corasick::*;
+pub use self::alternating_finite_automata::*;
+pub use self::compiled_automata::*;
+pub use self::conformance_testing::*;
 pub use self::deterministic_finite_automata::*;
+pub use self::generating_function::*;
+pub use self::matcher::*;
+pub use self::myhill_nerode::*;
+pub use self::nfa_simulation::*;
 pub use self::nondeterministic_finite_automata::*;
+pub use self::passive_learning::*;
+pub use self::pumping_lemma::*;
 pub use self::regular_grammar::*;
+pub use self::syntactic_monoid::*;
+pub use self::two_way_dfa::*;
+pub use self::weighted_automata::*;
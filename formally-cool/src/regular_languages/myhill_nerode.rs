@@ -0,0 +1,332 @@
+use super::deterministic_finite_automata::{set_to_state, DeterministicFiniteAutomata};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+use std::fmt;
+
+fn word_to_string(word: &str) -> &str {
+    if word.is_empty() {
+        "&"
+    } else {
+        word
+    }
+}
+
+fn ordered_pair(left: &str, right: &str) -> (String, String) {
+    if left <= right {
+        (left.to_string(), right.to_string())
+    } else {
+        (right.to_string(), left.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MyhillNerodeClass {
+    pub states: BTreeSet<String>,
+    pub access_string: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MyhillNerodeReport {
+    pub states: BTreeSet<String>,
+    pub classes: Vec<MyhillNerodeClass>,
+    pub distinguishing_suffixes: BTreeMap<(String, String), String>,
+}
+
+impl fmt::Display for MyhillNerodeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let access_width = self
+            .classes
+            .iter()
+            .map(|class| word_to_string(&class.access_string).len())
+            .max()
+            .unwrap_or(0)
+            + 3;
+
+        writeln!(f, "Myhill-Nerode classes:")?;
+        for class in &self.classes {
+            writeln!(
+                f,
+                "   {:width$}{}",
+                word_to_string(&class.access_string),
+                set_to_state(&class.states),
+                width = access_width,
+            )?;
+        }
+
+        let states: Vec<&String> = self.states.iter().collect();
+
+        let table_spacing = self
+            .states
+            .iter()
+            .map(|state| state.len())
+            .chain(self.distinguishing_suffixes.values().map(|suffix| {
+                if suffix.is_empty() {
+                    1
+                } else {
+                    suffix.len()
+                }
+            }))
+            .max()
+            .unwrap_or(0)
+            + 3;
+
+        write!(f, "Distinguishing suffixes:")?;
+        if states.len() < 2 {
+            return Ok(());
+        }
+
+        write!(f, "\n   {:width$}", "", width = table_spacing)?;
+        for state in &states[..states.len() - 1] {
+            write!(f, "{:width$}", state, width = table_spacing)?;
+        }
+
+        for (row, state) in states.iter().enumerate().skip(1) {
+            write!(f, "\n   {:width$}", state, width = table_spacing)?;
+            for column in &states[..row] {
+                let suffix = match self
+                    .distinguishing_suffixes
+                    .get(&ordered_pair(column, state))
+                {
+                    Some(suffix) => word_to_string(suffix),
+                    None => "=",
+                };
+                write!(f, "{:width$}", suffix, width = table_spacing)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn access_strings(&self) -> BTreeMap<String, String> {
+        let mut access_strings = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        access_strings.insert(self.start_state.clone(), String::new());
+        queue.push_back(self.start_state.clone());
+
+        while let Some(state) = queue.pop_front() {
+            let word = access_strings[&state].clone();
+
+            for letter in &self.alphabet {
+                if let Some(out_state) = self
+                    .transition_function
+                    .get(&(state.clone(), letter.clone()))
+                {
+                    if !access_strings.contains_key(out_state) {
+                        access_strings.insert(out_state.clone(), word.clone() + letter);
+                        queue.push_back(out_state.clone());
+                    }
+                }
+            }
+        }
+
+        access_strings
+    }
+
+    pub fn distinguishing_suffixes(&self) -> BTreeMap<(String, String), String> {
+        let automata = self.complete();
+        let states: Vec<&String> = automata.states.iter().collect();
+
+        // pairs split by acceptance are distinguished by the empty word
+        let mut suffixes = BTreeMap::new();
+
+        for (i, left) in states.iter().enumerate() {
+            for right in &states[(i + 1)..] {
+                if automata.accept_states.contains(*left) != automata.accept_states.contains(*right)
+                {
+                    suffixes.insert(((*left).clone(), (*right).clone()), String::new());
+                }
+            }
+        }
+
+        // round k only looks at pairs found in earlier rounds, so every suffix is a shortest one
+        loop {
+            let mut found = BTreeMap::new();
+
+            for (i, left) in states.iter().enumerate() {
+                for right in &states[(i + 1)..] {
+                    let pair = ((*left).clone(), (*right).clone());
+
+                    if suffixes.contains_key(&pair) {
+                        continue;
+                    }
+
+                    for letter in &automata.alphabet {
+                        let left_out =
+                            &automata.transition_function[&(pair.0.clone(), letter.clone())];
+                        let right_out =
+                            &automata.transition_function[&(pair.1.clone(), letter.clone())];
+
+                        if left_out == right_out {
+                            continue;
+                        }
+
+                        if let Some(suffix) = suffixes.get(&ordered_pair(left_out, right_out)) {
+                            found.insert(pair.clone(), letter.clone() + suffix);
+                            break;
+                        }
+                    }
+                }
+            }
+
+            if found.is_empty() {
+                break;
+            }
+
+            suffixes.extend(found);
+        }
+
+        suffixes
+            .into_iter()
+            .filter(|((left, right), _)| self.states.contains(left) && self.states.contains(right))
+            .collect()
+    }
+
+    pub fn myhill_nerode_report(&self) -> MyhillNerodeReport {
+        let automata = self.complete();
+        let access_strings = automata.access_strings();
+        let distinguishing_suffixes = automata.distinguishing_suffixes();
+
+        let mut reachable: Vec<(&String, &String)> = access_strings.iter().collect();
+        reachable.sort_by(|(_, left), (_, right)| (left.len(), left).cmp(&(right.len(), right)));
+
+        let mut classes: Vec<MyhillNerodeClass> = vec![];
+
+        for (state, access_string) in reachable {
+            let class = classes.iter_mut().find(|class| {
+                let representative = class.states.iter().next().unwrap();
+                !distinguishing_suffixes.contains_key(&ordered_pair(representative, state))
+            });
+
+            match class {
+                Some(class) => {
+                    class.states.insert(state.clone());
+                }
+                None => classes.push(MyhillNerodeClass {
+                    states: [state.clone()].iter().cloned().collect(),
+                    access_string: access_string.clone(),
+                }),
+            }
+        }
+
+        MyhillNerodeReport {
+            states: automata.states,
+            classes,
+            distinguishing_suffixes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_automata() -> DeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("A"), String::from("0")), String::from("B"));
+        hash.insert((String::from("A"), String::from("1")), String::from("C"));
+        hash.insert((String::from("B"), String::from("0")), String::from("A"));
+        hash.insert((String::from("B"), String::from("1")), String::from("D"));
+        hash.insert((String::from("C"), String::from("0")), String::from("E"));
+        hash.insert((String::from("C"), String::from("1")), String::from("F"));
+        hash.insert((String::from("D"), String::from("0")), String::from("E"));
+        hash.insert((String::from("D"), String::from("1")), String::from("F"));
+        hash.insert((String::from("E"), String::from("0")), String::from("E"));
+        hash.insert((String::from("E"), String::from("1")), String::from("F"));
+        hash.insert((String::from("F"), String::from("0")), String::from("F"));
+        hash.insert((String::from("F"), String::from("1")), String::from("F"));
+
+        DeterministicFiniteAutomata {
+            states: ["A", "B", "C", "D", "E", "F"]
+                .iter()
+                .map(|state| state.to_string())
+                .collect(),
+            alphabet: ["0".to_string(), "1".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("A"),
+            accept_states: ["C", "D", "E"]
+                .iter()
+                .map(|state| state.to_string())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn distinguishing_suffixes() {
+        let automata = make_automata();
+
+        let suffixes = automata.distinguishing_suffixes();
+
+        assert_eq!(suffixes.get(&ordered_pair("A", "B")), None);
+        assert_eq!(suffixes.get(&ordered_pair("C", "E")), None);
+        assert_eq!(suffixes[&ordered_pair("A", "C")], "");
+        assert_eq!(suffixes[&ordered_pair("A", "F")], "1");
+        assert_eq!(suffixes[&ordered_pair("C", "F")], "");
+        assert_eq!(suffixes.len(), 15 - 4);
+
+        for ((left, right), suffix) in &suffixes {
+            let mut automata_left = automata.clone();
+            automata_left.start_state = left.clone();
+            let mut automata_right = automata.clone();
+            automata_right.start_state = right.clone();
+
+            assert_ne!(
+                automata_left.compute(suffix),
+                automata_right.compute(suffix)
+            );
+        }
+    }
+
+    #[test]
+    fn myhill_nerode_report() {
+        let report = make_automata().myhill_nerode_report();
+
+        let classes = vec![
+            MyhillNerodeClass {
+                states: ["A", "B"].iter().map(|state| state.to_string()).collect(),
+                access_string: String::new(),
+            },
+            MyhillNerodeClass {
+                states: ["C", "D", "E"]
+                    .iter()
+                    .map(|state| state.to_string())
+                    .collect(),
+                access_string: String::from("1"),
+            },
+            MyhillNerodeClass {
+                states: ["F"].iter().map(|state| state.to_string()).collect(),
+                access_string: String::from("11"),
+            },
+        ];
+
+        assert_eq!(report.classes, classes);
+    }
+
+    #[test]
+    fn myhill_nerode_report_partial_automata() {
+        let mut automata = make_automata();
+
+        automata
+            .transition_function
+            .remove(&(String::from("F"), String::from("0")));
+        automata
+            .transition_function
+            .remove(&(String::from("F"), String::from("1")));
+
+        let report = automata.myhill_nerode_report();
+
+        assert_eq!(report.classes.len(), 3);
+        assert!(report.states.contains("dead_0"));
+        assert!(report.classes[2].states.contains("dead_0"));
+        assert_eq!(
+            report
+                .distinguishing_suffixes
+                .get(&ordered_pair("F", "dead_0")),
+            None
+        );
+    }
+}
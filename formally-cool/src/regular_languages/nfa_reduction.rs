@@ -0,0 +1,438 @@
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+type Edge = (usize, usize, usize);
+
+// epsilon free automaton with states and letters replaced by their indices
+struct IndexedAutomata {
+    states: Vec<String>,
+    letters: Vec<String>,
+    edges: BTreeSet<Edge>,
+    start: usize,
+    accepting: Vec<bool>,
+}
+
+impl IndexedAutomata {
+    fn new(automata: &NondeterministicFiniteAutomata) -> Self {
+        // the start state and the ends of the transitions get a state even when undeclared
+        let mut states: BTreeSet<String> = automata.states.clone();
+        states.insert(automata.start_state.clone());
+        for ((state, _), targets) in &automata.transition_function {
+            states.insert(state.clone());
+            states.extend(targets.iter().cloned());
+        }
+        let states: Vec<String> = states.into_iter().collect();
+        let letters: Vec<String> = automata
+            .alphabet
+            .iter()
+            .filter(|letter| *letter != "&")
+            .cloned()
+            .collect();
+
+        let state_index: BTreeMap<&String, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state, i))
+            .collect();
+        let letter_index: BTreeMap<&String, usize> = letters
+            .iter()
+            .enumerate()
+            .map(|(i, letter)| (letter, i))
+            .collect();
+
+        let mut edges = BTreeSet::new();
+        for ((state, letter), targets) in &automata.transition_function {
+            if let (Some(state), Some(letter)) = (state_index.get(state), letter_index.get(letter))
+            {
+                for target in targets {
+                    if let Some(target) = state_index.get(target) {
+                        edges.insert((*state, *letter, *target));
+                    }
+                }
+            }
+        }
+
+        IndexedAutomata {
+            accepting: states
+                .iter()
+                .map(|state| automata.accept_states.contains(state))
+                .collect(),
+            start: state_index[&automata.start_state],
+            states,
+            letters,
+            edges,
+        }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.states.len(), self.edges.len())
+    }
+
+    // keeps the states that are reachable from the start and can reach an accept state
+    fn trim(&mut self) {
+        let mut reachable = vec![false; self.states.len()];
+        reachable[self.start] = true;
+        let mut productive = self.accepting.clone();
+
+        let mut stop = false;
+        while !stop {
+            stop = true;
+            for (state, _, target) in &self.edges {
+                if reachable[*state] && !reachable[*target] {
+                    reachable[*target] = true;
+                    stop = false;
+                }
+                if productive[*target] && !productive[*state] {
+                    productive[*state] = true;
+                    stop = false;
+                }
+            }
+        }
+
+        let keep: Vec<bool> = (0..self.states.len())
+            .map(|state| state == self.start || (reachable[state] && productive[state]))
+            .collect();
+
+        self.rename(&(0..self.states.len()).collect::<Vec<_>>(), &keep);
+    }
+
+    // merges every state into representative[state] and drops the states not kept
+    fn rename(&mut self, representative: &[usize], keep: &[bool]) {
+        let mut new_index = vec![None; self.states.len()];
+        let mut states = vec![];
+        let mut accepting = vec![];
+
+        for state in 0..self.states.len() {
+            if keep[state] && representative[state] == state {
+                new_index[state] = Some(states.len());
+                states.push(self.states[state].clone());
+                accepting.push(false);
+            }
+        }
+
+        for state in 0..self.states.len() {
+            if let Some(index) = new_index[representative[state]] {
+                if keep[state] && self.accepting[state] {
+                    accepting[index] = true;
+                }
+            }
+        }
+
+        self.edges = self
+            .edges
+            .iter()
+            .filter(|(state, _, target)| keep[*state] && keep[*target])
+            .filter_map(|(state, letter, target)| {
+                match (
+                    new_index[representative[*state]],
+                    new_index[representative[*target]],
+                ) {
+                    (Some(state), Some(target)) => Some((state, *letter, target)),
+                    _ => None,
+                }
+            })
+            .collect();
+        self.start = new_index[representative[self.start]].unwrap();
+        self.states = states;
+        self.accepting = accepting;
+    }
+
+    // greatest relation where related[p][q] means q simulates p; backwards the edges are read
+    // reversed and only the start state is final. simulating[letter][p][q] counts the letter
+    // successors of q that still simulate p, and once it falls to zero no letter predecessor
+    // of p is simulated by q; each dropped pair is handled once, so the work is about the
+    // number of states times the number of edges, with states squared times letters memory
+    fn simulation(&self, backward: bool) -> Vec<Vec<bool>> {
+        let count = self.states.len();
+        let letters = self.letters.len();
+
+        let edges: Vec<Edge> = if backward {
+            self.edges
+                .iter()
+                .map(|(state, letter, target)| (*target, *letter, *state))
+                .collect()
+        } else {
+            self.edges.iter().cloned().collect()
+        };
+        let is_final: Vec<bool> = if backward {
+            (0..count).map(|state| state == self.start).collect()
+        } else {
+            self.accepting.clone()
+        };
+
+        let mut successors: Vec<Vec<Vec<usize>>> = vec![vec![vec![]; letters]; count];
+        let mut predecessors: Vec<Vec<Vec<usize>>> = vec![vec![vec![]; letters]; count];
+        for (state, letter, target) in &edges {
+            successors[*state][*letter].push(*target);
+            predecessors[*target][*letter].push(*state);
+        }
+
+        let mut related: Vec<Vec<bool>> = (0..count)
+            .map(|p| (0..count).map(|q| !is_final[p] || is_final[q]).collect())
+            .collect();
+
+        let mut simulating = vec![vec![vec![0usize; count]; count]; letters];
+        for (letter, letter_simulating) in simulating.iter_mut().enumerate() {
+            for (p, row) in letter_simulating.iter_mut().enumerate() {
+                for (q, simulating_count) in row.iter_mut().enumerate() {
+                    *simulating_count = successors[q][letter]
+                        .iter()
+                        .filter(|q_next| related[p][**q_next])
+                        .count();
+                }
+            }
+        }
+
+        let mut worklist = vec![];
+        for (letter, letter_simulating) in simulating.iter().enumerate() {
+            for (p_next, row) in letter_simulating.iter().enumerate() {
+                for (q, simulating_count) in row.iter().enumerate() {
+                    if *simulating_count == 0 {
+                        for p in &predecessors[p_next][letter] {
+                            if related[*p][q] {
+                                related[*p][q] = false;
+                                worklist.push((*p, q));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        while let Some((p_next, q_next)) = worklist.pop() {
+            for letter in 0..letters {
+                for q in &predecessors[q_next][letter] {
+                    simulating[letter][p_next][*q] -= 1;
+                    if simulating[letter][p_next][*q] == 0 {
+                        for p in &predecessors[p_next][letter] {
+                            if related[*p][*q] {
+                                related[*p][*q] = false;
+                                worklist.push((*p, *q));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        related
+    }
+
+    fn quotient(&mut self, related: &[Vec<bool>]) {
+        let representative: Vec<usize> = (0..self.states.len())
+            .map(|p| {
+                (0..self.states.len())
+                    .find(|q| related[p][*q] && related[*q][p])
+                    .unwrap()
+            })
+            .collect();
+
+        self.rename(&representative, &vec![true; self.states.len()]);
+    }
+
+    // an edge is redundant when a sibling edge on the same letter leads to a state that
+    // strictly simulates its target (forward) or comes from one that strictly simulates its
+    // source (backward)
+    fn prune(&mut self, related: &[Vec<bool>], backward: bool) {
+        let strictly = |p: usize, q: usize| related[p][q] && !related[q][p];
+
+        // siblings share the source and letter forward, the target and letter backward
+        let mut siblings: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+        for (state, letter, target) in &self.edges {
+            if backward {
+                siblings.entry((*target, *letter)).or_default().push(*state);
+            } else {
+                siblings.entry((*state, *letter)).or_default().push(*target);
+            }
+        }
+
+        let redundant: Vec<Edge> = self
+            .edges
+            .iter()
+            .filter(|(state, letter, target)| {
+                if backward {
+                    siblings[&(*target, *letter)]
+                        .iter()
+                        .any(|other_state| strictly(*state, *other_state))
+                } else {
+                    siblings[&(*state, *letter)]
+                        .iter()
+                        .any(|other_target| strictly(*target, *other_target))
+                }
+            })
+            .cloned()
+            .collect();
+
+        for edge in redundant {
+            self.edges.remove(&edge);
+        }
+    }
+
+    fn to_automata(&self) -> NondeterministicFiniteAutomata {
+        let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+
+        for (state, letter, target) in &self.edges {
+            transition_function
+                .entry((self.states[*state].clone(), self.letters[*letter].clone()))
+                .or_default()
+                .insert(self.states[*target].clone());
+        }
+
+        NondeterministicFiniteAutomata {
+            states: self.states.iter().cloned().collect(),
+            alphabet: self.letters.iter().cloned().collect(),
+            transition_function,
+            start_state: self.states[self.start].clone(),
+            accept_states: self
+                .states
+                .iter()
+                .zip(&self.accepting)
+                .filter(|(_, accepting)| **accepting)
+                .map(|(state, _)| state.clone())
+                .collect(),
+        }
+    }
+}
+
+impl NondeterministicFiniteAutomata {
+    // a state takes over the letter transitions of its epsilon closure and accepts when the
+    // closure does
+    pub fn remove_epsilon_transitions(&self) -> Self {
+        let epsilon_closures = self.epsilon_closures();
+
+        let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        for (state, closure) in &epsilon_closures {
+            if !closure.is_disjoint(&self.accept_states) {
+                accept_states.insert(state.clone());
+            }
+
+            for reached in closure {
+                for letter in self.alphabet.iter().filter(|letter| *letter != "&") {
+                    if let Some(targets) = self
+                        .transition_function
+                        .get(&(reached.clone(), letter.clone()))
+                    {
+                        transition_function
+                            .entry((state.clone(), letter.clone()))
+                            .or_default()
+                            .extend(targets.iter().cloned());
+                    }
+                }
+            }
+        }
+
+        NondeterministicFiniteAutomata {
+            states: self.states.clone(),
+            alphabet: self
+                .alphabet
+                .iter()
+                .filter(|letter| *letter != "&")
+                .cloned()
+                .collect(),
+            transition_function,
+            start_state: self.start_state.clone(),
+            accept_states,
+        }
+    }
+
+    // quotients by forward and backward simulation equivalence and prunes the transitions
+    // made redundant by strict simulation, until neither changes the automaton; each merged
+    // state keeps the name of one of its members
+    pub fn reduce(&self) -> Self {
+        let mut automata = IndexedAutomata::new(&self.remove_epsilon_transitions());
+        automata.trim();
+
+        loop {
+            let size = automata.size();
+
+            for backward in &[false, true] {
+                let related = automata.simulation(*backward);
+                automata.quotient(&related);
+
+                let related = automata.simulation(*backward);
+                automata.prune(&related, *backward);
+                automata.trim();
+            }
+
+            if automata.size() == size {
+                break;
+            }
+        }
+
+        automata.to_automata()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::{ab, renamed};
+
+    fn assert_equivalent(
+        left: &NondeterministicFiniteAutomata,
+        right: &NondeterministicFiniteAutomata,
+    ) {
+        assert_eq!(
+            left.determinize().find_counterexample(&right.determinize()),
+            None
+        );
+    }
+
+    #[test]
+    fn reduce_union_of_copies() {
+        let contains = NondeterministicFiniteAutomata::from(&catalog::contains("aba", &ab()));
+
+        let union = renamed(&contains, "l").union(&renamed(&contains, "r"));
+        let reduced = union.reduce();
+
+        assert_equivalent(&union, &reduced);
+        assert_eq!(reduced.states.len(), contains.states.len());
+    }
+
+    #[test]
+    fn reduce_union() {
+        let contains = NondeterministicFiniteAutomata::from(&catalog::contains("ab", &ab()));
+        let ends_with = NondeterministicFiniteAutomata::from(&catalog::ends_with("ab", &ab()));
+
+        let union = renamed(&contains, "c").union(&renamed(&ends_with, "e"));
+        let reduced = union.reduce();
+
+        assert_equivalent(&union, &reduced);
+        assert!(reduced.states.len() < union.states.len());
+        assert!(!reduced.alphabet.contains("&"));
+    }
+
+    #[test]
+    fn reduce_with_epsilon_transitions() {
+        let automata =
+            NondeterministicFiniteAutomata::damerau_levenshtein_automaton("abba", 2, &ab());
+
+        let without_epsilon = automata.remove_epsilon_transitions();
+        assert!(without_epsilon
+            .transition_function
+            .keys()
+            .all(|(_, letter)| letter != "&"));
+        assert_equivalent(&automata, &without_epsilon);
+
+        let reduced = automata.reduce();
+
+        assert_equivalent(&automata, &reduced);
+        assert!(reduced.states.len() < automata.states.len());
+    }
+
+    #[test]
+    fn reduce_undeclared_start() {
+        let mut automata = NondeterministicFiniteAutomata::from(&catalog::contains("ab", &ab()));
+        let start_state = automata.start_state.clone();
+        automata.states.remove(&start_state);
+
+        let reduced = automata.reduce();
+
+        assert_eq!(reduced.start_state, start_state);
+        assert_equivalent(&automata, &reduced);
+    }
+}
@@ -0,0 +1,289 @@
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+fn mask_insert(mask: &mut [u64], index: usize) {
+    mask[index / 64] |= 1 << (index % 64);
+}
+
+fn mask_union(mask: &mut [u64], other: &[u64]) {
+    for (word, other_word) in mask.iter_mut().zip(other) {
+        *word |= other_word;
+    }
+}
+
+// the active states live in a bitset; every transition mask already includes the epsilon
+// closure of its targets, so a step only ORs the masks of the active states together. input is
+// read one character at a time, so every letter but the epsilon one has to be a single character
+#[derive(Debug, Clone)]
+pub struct NfaSimulator {
+    states: Vec<String>,
+    columns: BTreeMap<char, usize>,
+    // successors[column][state] is the closed set reached from state reading that letter
+    successors: Vec<Vec<Vec<u64>>>,
+    start: Vec<u64>,
+    accepting: Vec<u64>,
+}
+
+pub struct NfaRun<'a> {
+    simulator: &'a NfaSimulator,
+    active: Vec<u64>,
+}
+
+impl<'a> NfaRun<'a> {
+    pub fn feed(&mut self, chunk: &str) {
+        for symbol in chunk.chars() {
+            if self.active.iter().all(|word| *word == 0) {
+                return;
+            }
+            self.active = self.simulator.step(&self.active, symbol);
+        }
+    }
+
+    pub fn is_match(&self) -> bool {
+        self.active
+            .iter()
+            .zip(&self.simulator.accepting)
+            .any(|(active, accepting)| active & accepting != 0)
+    }
+
+    pub fn active_states(&self) -> Vec<&str> {
+        self.simulator.states_in(&self.active)
+    }
+}
+
+impl NfaSimulator {
+    // None when some letter is longer than one character or the start state is not declared;
+    // states only mentioned by a transition still get a place in the bitsets
+    pub fn new(automata: &NondeterministicFiniteAutomata) -> Option<Self> {
+        if !automata.states.contains(&automata.start_state) {
+            return None;
+        }
+
+        let mut states: BTreeSet<String> = automata.states.clone();
+        for ((state, _), targets) in &automata.transition_function {
+            states.insert(state.clone());
+            states.extend(targets.iter().cloned());
+        }
+        let states: Vec<String> = states.into_iter().collect();
+
+        let index: BTreeMap<&String, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state, i))
+            .collect();
+        let width = states.len() / 64 + 1;
+
+        let epsilon_closures = automata.epsilon_closures();
+
+        let mut closures = vec![vec![0; width]; states.len()];
+        for (i, state) in states.iter().enumerate() {
+            mask_insert(&mut closures[i], i);
+            if let Some(closure) = epsilon_closures.get(state) {
+                for target in closure {
+                    mask_insert(&mut closures[i], index[target]);
+                }
+            }
+        }
+
+        let mut columns = BTreeMap::new();
+        let mut successors = vec![];
+
+        for letter in &automata.alphabet {
+            let mut symbols = letter.chars();
+            let symbol = match (symbols.next(), symbols.next()) {
+                _ if letter == "&" => continue,
+                (Some(symbol), None) => symbol,
+                _ => return None,
+            };
+
+            let mut letter_successors = vec![vec![0; width]; states.len()];
+            for (i, state) in states.iter().enumerate() {
+                if let Some(targets) = automata
+                    .transition_function
+                    .get(&(state.clone(), letter.clone()))
+                {
+                    for target in targets {
+                        mask_union(&mut letter_successors[i], &closures[index[target]]);
+                    }
+                }
+            }
+
+            columns.insert(symbol, successors.len());
+            successors.push(letter_successors);
+        }
+
+        let mut accepting = vec![0; width];
+        for state in &automata.accept_states {
+            if let Some(state) = index.get(state) {
+                mask_insert(&mut accepting, *state);
+            }
+        }
+
+        let start = closures[index[&automata.start_state]].clone();
+
+        Some(NfaSimulator {
+            states,
+            columns,
+            successors,
+            start,
+            accepting,
+        })
+    }
+
+    fn step(&self, active: &[u64], symbol: char) -> Vec<u64> {
+        let mut next = vec![0; active.len()];
+
+        if let Some(column) = self.columns.get(&symbol) {
+            for (i, word) in active.iter().enumerate() {
+                let mut word = *word;
+                while word != 0 {
+                    let state = i * 64 + word.trailing_zeros() as usize;
+                    mask_union(&mut next, &self.successors[*column][state]);
+                    word &= word - 1;
+                }
+            }
+        }
+
+        next
+    }
+
+    fn states_in(&self, mask: &[u64]) -> Vec<&str> {
+        self.states
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask[i / 64] & (1 << (i % 64)) != 0)
+            .map(|(_, state)| state.as_str())
+            .collect()
+    }
+
+    pub fn run(&self) -> NfaRun<'_> {
+        NfaRun {
+            simulator: self,
+            active: self.start.clone(),
+        }
+    }
+
+    pub fn compute(&self, input: &str) -> bool {
+        let mut run = self.run();
+        run.feed(input);
+        run.is_match()
+    }
+}
+
+impl NondeterministicFiniteAutomata {
+    pub fn simulator(&self) -> Option<NfaSimulator> {
+        NfaSimulator::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::words;
+    use std::collections::BTreeSet;
+
+    // an a among the last n letters, checked by a chain of n states that epsilon moves can skip
+    fn make_automata_a_within_last(n: usize) -> NondeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        let state = |i: usize| String::from("q") + &i.to_string();
+
+        hash.insert(
+            (state(0), String::from("a")),
+            [state(0), state(1)]
+                .iter()
+                .cloned()
+                .collect::<BTreeSet<_>>(),
+        );
+        hash.insert(
+            (state(0), String::from("b")),
+            [state(0)].iter().cloned().collect(),
+        );
+        for i in 1..n {
+            hash.insert(
+                (state(i), String::from("&")),
+                [state(i + 1)].iter().cloned().collect(),
+            );
+            for letter in &["a", "b"] {
+                hash.insert(
+                    (state(i), letter.to_string()),
+                    [state(i + 1)].iter().cloned().collect(),
+                );
+            }
+        }
+
+        NondeterministicFiniteAutomata {
+            states: (0..=n).map(state).collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: state(0),
+            accept_states: [state(n)].iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn compute_matches_determinization() {
+        let alphabet = ["a", "b", "c"].iter().map(|x| x.to_string()).collect();
+        let automata =
+            NondeterministicFiniteAutomata::damerau_levenshtein_automaton("abcab", 2, &alphabet);
+
+        let simulator = automata.simulator().unwrap();
+        let determinized = automata.determinize();
+
+        for word in words(&["a", "b", "c"], 7) {
+            assert_eq!(simulator.compute(&word), determinized.compute(&word));
+        }
+    }
+
+    #[test]
+    fn wide_automata() {
+        let automata = make_automata_a_within_last(70);
+        let simulator = automata.simulator().unwrap();
+
+        // 71 states do not fit in a single word of the masks
+        assert!(simulator.compute("bbba"));
+        assert!(simulator.compute(&(String::from("a") + &"b".repeat(69))));
+        assert!(!simulator.compute(&(String::from("a") + &"b".repeat(70))));
+        assert!(!simulator.compute("bbbb"));
+        assert!(!simulator.compute("abc"));
+
+        let mut run = simulator.run();
+        run.feed("ab");
+        assert_eq!(run.active_states().len(), 70);
+        run.feed("c");
+        assert!(run.active_states().is_empty());
+    }
+
+    #[test]
+    fn multi_character_letters() {
+        let mut automata = make_automata_a_within_last(2);
+
+        automata.alphabet.insert(String::from("ab"));
+        automata.transition_function.insert(
+            (String::from("q0"), String::from("ab")),
+            [String::from("q2")].iter().cloned().collect(),
+        );
+
+        assert!(automata.simulator().is_none());
+    }
+
+    #[test]
+    fn undeclared_states() {
+        let mut automata = make_automata_a_within_last(2);
+
+        automata.alphabet.insert(String::from("c"));
+        automata.transition_function.insert(
+            (String::from("q0"), String::from("c")),
+            [String::from("elsewhere")].iter().cloned().collect(),
+        );
+        automata.accept_states.insert(String::from("elsewhere"));
+
+        let simulator = automata.simulator().unwrap();
+        assert!(simulator.compute("c"));
+        assert!(!simulator.compute("cc"));
+
+        automata.start_state = String::from("nowhere");
+        assert!(automata.simulator().is_none());
+    }
+}
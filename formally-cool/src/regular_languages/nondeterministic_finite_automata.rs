@@ -3,6 +3,7 @@ use super::regular_grammar::RegularGrammar;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::collections::VecDeque;
 use std::fmt;
 
 fn symbol_to_state(symbol: &String) -> String {
@@ -262,4 +263,94 @@ impl NondeterministicFiniteAutomata {
             accept_states: accept_states,
         }
     }
+
+    // also covers the start state and the ends of the transitions when they are undeclared
+    pub fn epsilon_closures(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut epsilon_closures = BTreeMap::new();
+
+        let mut states = self.states.clone();
+        states.insert(self.start_state.clone());
+        for ((state, _), targets) in &self.transition_function {
+            states.insert(state.clone());
+            states.extend(targets.iter().cloned());
+        }
+
+        for state in &states {
+            let mut closure = BTreeSet::new();
+            let mut stack = vec![state.clone()];
+
+            while let Some(state) = stack.pop() {
+                if !closure.insert(state.clone()) {
+                    continue;
+                }
+                if let Some(set) = self.transition_function.get(&(state, String::from("&"))) {
+                    stack.extend(set.iter().cloned());
+                }
+            }
+
+            epsilon_closures.insert(state.clone(), closure);
+        }
+
+        epsilon_closures
+    }
+
+    // subset construction restricted to the subsets reachable from the start closure, the empty
+    // set shows up as the "()" sink whenever some letter leads nowhere
+    pub fn determinize(&self) -> DeterministicFiniteAutomata {
+        let epsilon_closures = self.epsilon_closures();
+        let alphabet: BTreeSet<String> = self
+            .alphabet
+            .iter()
+            .filter(|letter| *letter != "&")
+            .cloned()
+            .collect();
+
+        let start_set = epsilon_closures[&self.start_state].clone();
+
+        let mut states = BTreeSet::new();
+        let mut transition_function = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+        let mut queue = VecDeque::new();
+
+        states.insert(set_to_state(&start_set));
+        queue.push_back(start_set.clone());
+
+        while let Some(set) = queue.pop_front() {
+            let state = set_to_state(&set);
+
+            if !set.is_disjoint(&self.accept_states) {
+                accept_states.insert(state.clone());
+            }
+
+            for letter in &alphabet {
+                let mut out_set = BTreeSet::new();
+
+                for state in &set {
+                    if let Some(targets) = self
+                        .transition_function
+                        .get(&(state.clone(), letter.clone()))
+                    {
+                        for target in targets {
+                            out_set.extend(epsilon_closures[target].iter().cloned());
+                        }
+                    }
+                }
+
+                let out_state = set_to_state(&out_set);
+                if states.insert(out_state.clone()) {
+                    queue.push_back(out_set);
+                }
+
+                transition_function.insert((state.clone(), letter.clone()), out_state);
+            }
+        }
+
+        DeterministicFiniteAutomata {
+            states,
+            alphabet,
+            transition_function,
+            start_state: set_to_state(&start_set),
+            accept_states,
+        }
+    }
 }
@@ -0,0 +1,273 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+// states are named after their prefix in brackets, so the empty one can not clash with a word
+// of the samples
+fn prefix_to_state(prefix: &str) -> String {
+    String::from("(") + prefix + ")"
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateMerge {
+    pub red: String,
+    pub blue: String,
+}
+
+#[derive(Clone)]
+struct PrefixTree {
+    transitions: Vec<BTreeMap<char, usize>>,
+    labels: Vec<Option<bool>>,
+}
+
+impl PrefixTree {
+    // folds the tree hanging from blue into red, failing if an accepted and a rejected sample
+    // would end up in the same state
+    fn fold(&mut self, red: usize, blue: usize) -> bool {
+        if let Some(label) = self.labels[blue] {
+            match self.labels[red] {
+                Some(red_label) if red_label != label => return false,
+                _ => self.labels[red] = Some(label),
+            }
+        }
+
+        for (symbol, blue_child) in self.transitions[blue].clone() {
+            match self.transitions[red].get(&symbol) {
+                Some(&red_child) => {
+                    if !self.fold(red_child, blue_child) {
+                        return false;
+                    }
+                }
+                None => {
+                    self.transitions[red].insert(symbol, blue_child);
+                }
+            }
+        }
+
+        true
+    }
+
+    fn merge(&self, red_states: &BTreeSet<usize>, red: usize, blue: usize) -> Option<Self> {
+        let mut tree = self.clone();
+
+        for state in red_states {
+            for child in tree.transitions[*state].values_mut() {
+                if *child == blue {
+                    *child = red;
+                }
+            }
+        }
+
+        if tree.fold(red, blue) {
+            Some(tree)
+        } else {
+            None
+        }
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    // RPNI: build the prefix tree acceptor of the samples, then walk the blue frontier in
+    // shortlex order merging each blue state into the first compatible red one
+    pub fn infer_from_samples(
+        positive: &[&str],
+        negative: &[&str],
+    ) -> Option<(Self, Vec<StateMerge>)> {
+        let mut prefixes = BTreeSet::new();
+
+        for sample in positive.iter().chain(negative.iter()) {
+            let symbols: Vec<char> = sample.chars().collect();
+            for length in 0..=symbols.len() {
+                prefixes.insert(symbols[..length].iter().collect::<String>());
+            }
+        }
+
+        let mut prefixes: Vec<String> = prefixes.into_iter().collect();
+        prefixes.sort_by(|left, right| {
+            (left.chars().count(), left).cmp(&(right.chars().count(), right))
+        });
+
+        let index: BTreeMap<&String, usize> = prefixes
+            .iter()
+            .enumerate()
+            .map(|(i, prefix)| (prefix, i))
+            .collect();
+
+        let mut tree = PrefixTree {
+            transitions: vec![BTreeMap::new(); prefixes.len()],
+            labels: vec![None; prefixes.len()],
+        };
+
+        for prefix in prefixes.iter().skip(1) {
+            let mut parent = prefix.clone();
+            let symbol = parent.pop().unwrap();
+            tree.transitions[index[&parent]].insert(symbol, index[prefix]);
+        }
+
+        for (samples, label) in &[(positive, true), (negative, false)] {
+            for sample in samples.iter() {
+                let state = index[&sample.to_string()];
+                if tree.labels[state] == Some(!label) {
+                    return None;
+                }
+                tree.labels[state] = Some(*label);
+            }
+        }
+
+        let mut red_states = BTreeSet::new();
+        red_states.insert(0);
+
+        let mut merges = vec![];
+
+        loop {
+            let blue_states: BTreeSet<usize> = red_states
+                .iter()
+                .flat_map(|state| tree.transitions[*state].values().cloned())
+                .filter(|state| !red_states.contains(state))
+                .collect();
+
+            let blue = match blue_states.iter().next() {
+                Some(blue) => *blue,
+                None => break,
+            };
+
+            let merge = red_states.iter().find_map(|red| {
+                tree.merge(&red_states, *red, blue)
+                    .map(|merged_tree| (*red, merged_tree))
+            });
+
+            match merge {
+                Some((red, merged_tree)) => {
+                    tree = merged_tree;
+                    merges.push(StateMerge {
+                        red: prefix_to_state(&prefixes[red]),
+                        blue: prefix_to_state(&prefixes[blue]),
+                    });
+                }
+                None => {
+                    red_states.insert(blue);
+                }
+            }
+        }
+
+        let mut transition_function = BTreeMap::new();
+
+        for state in &red_states {
+            for (symbol, out_state) in &tree.transitions[*state] {
+                transition_function.insert(
+                    (prefix_to_state(&prefixes[*state]), symbol.to_string()),
+                    prefix_to_state(&prefixes[*out_state]),
+                );
+            }
+        }
+
+        let automata = DeterministicFiniteAutomata {
+            states: red_states
+                .iter()
+                .map(|state| prefix_to_state(&prefixes[*state]))
+                .collect(),
+            alphabet: prefixes
+                .iter()
+                .flat_map(|prefix| prefix.chars())
+                .map(|symbol| symbol.to_string())
+                .collect(),
+            transition_function,
+            start_state: prefix_to_state(&prefixes[0]),
+            accept_states: red_states
+                .iter()
+                .filter(|state| tree.labels[**state] == Some(true))
+                .map(|state| prefix_to_state(&prefixes[*state]))
+                .collect(),
+        };
+
+        Some((automata, merges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::make_automata_ends_with_ab;
+
+    #[test]
+    fn infer_from_samples() {
+        let positive = ["", "aa", "bb", "aaaa", "abab", "baba", "bbaa"];
+        let negative = ["a", "b", "ab", "ba", "aaa", "aab", "bbb"];
+
+        let (automata, merges) =
+            DeterministicFiniteAutomata::infer_from_samples(&positive, &negative).unwrap();
+
+        for sample in &positive {
+            assert!(automata.compute(sample));
+        }
+        for sample in &negative {
+            assert!(!automata.compute(sample));
+        }
+
+        let merged: Vec<(&str, &str)> = merges
+            .iter()
+            .map(|merge| (merge.red.as_str(), merge.blue.as_str()))
+            .collect();
+
+        assert_eq!(
+            merged,
+            vec![
+                ("()", "(aa)"),
+                ("(b)", "(ab)"),
+                ("(b)", "(ba)"),
+                ("(bb)", "(bba)"),
+                ("(a)", "(bbb)")
+            ]
+        );
+        assert_eq!(automata.states.len(), 4);
+    }
+
+    #[test]
+    fn infer_from_samples_identifies_target() {
+        let target = make_automata_ends_with_ab();
+
+        let mut words = vec![String::new()];
+        let mut samples = vec![String::new()];
+
+        for _ in 0..5 {
+            words = words
+                .iter()
+                .flat_map(|word| vec![word.clone() + "a", word.clone() + "b"])
+                .collect();
+            samples.extend(words.iter().cloned());
+        }
+
+        let positive: Vec<&str> = samples
+            .iter()
+            .filter(|word| target.compute(word))
+            .map(|word| word.as_str())
+            .collect();
+        let negative: Vec<&str> = samples
+            .iter()
+            .filter(|word| !target.compute(word))
+            .map(|word| word.as_str())
+            .collect();
+
+        let (automata, _) =
+            DeterministicFiniteAutomata::infer_from_samples(&positive, &negative).unwrap();
+
+        assert_eq!(automata.states.len(), 3);
+        assert_eq!(target.find_counterexample(&automata), None);
+    }
+
+    #[test]
+    fn infer_from_samples_with_epsilon_letter() {
+        let (automata, _) =
+            DeterministicFiniteAutomata::infer_from_samples(&["", "&&"], &["&"]).unwrap();
+
+        assert!(automata.compute(""));
+        assert!(automata.compute("&&"));
+        assert!(!automata.compute("&"));
+        assert_eq!(automata.states.len(), 2);
+    }
+
+    #[test]
+    fn infer_from_inconsistent_samples() {
+        assert!(DeterministicFiniteAutomata::infer_from_samples(&["ab"], &["ab"]).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PumpingDecomposition {
+    pub x: String,
+    pub y: String,
+    pub z: String,
+    pub state: String,
+}
+
+impl PumpingDecomposition {
+    pub fn pump(&self, i: usize) -> String {
+        self.x.clone() + &self.y.repeat(i) + &self.z
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn pumping_decomposition(&self, input: &str) -> Option<PumpingDecomposition> {
+        let symbols: Vec<char> = input.chars().collect();
+
+        if symbols.len() < self.states.len() || !self.compute(input) {
+            return None;
+        }
+
+        // the first state seen twice on the run closes the loop read by y, so |xy| <= |Q|
+        let mut visited = BTreeMap::new();
+        let mut actual_state = self.start_state.clone();
+
+        visited.insert(actual_state.clone(), 0);
+
+        for (position, symbol) in symbols.iter().enumerate() {
+            actual_state = self.transition_function[&(actual_state, symbol.to_string())].clone();
+
+            match visited.get(&actual_state) {
+                Some(&loop_start) => {
+                    return Some(PumpingDecomposition {
+                        x: symbols[..loop_start].iter().collect(),
+                        y: symbols[loop_start..(position + 1)].iter().collect(),
+                        z: symbols[(position + 1)..].iter().collect(),
+                        state: actual_state,
+                    });
+                }
+                None => {
+                    visited.insert(actual_state.clone(), position + 1);
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn verify_pumping<I>(&self, decomposition: &PumpingDecomposition, range: I) -> bool
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        range
+            .into_iter()
+            .all(|i| self.compute(&decomposition.pump(i)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::make_automata_ends_with_ab;
+
+    #[test]
+    fn pumping_decomposition() {
+        let automata = make_automata_ends_with_ab();
+
+        let decomposition = automata.pumping_decomposition("bbab").unwrap();
+
+        assert_eq!(
+            decomposition,
+            PumpingDecomposition {
+                x: String::from(""),
+                y: String::from("b"),
+                z: String::from("bab"),
+                state: String::from("q0"),
+            }
+        );
+        assert_eq!(decomposition.pump(3), String::from("bbbbab"));
+        assert!(automata.verify_pumping(&decomposition, 0..10));
+
+        let decomposition = automata.pumping_decomposition("aabab").unwrap();
+
+        assert_eq!(decomposition.x, String::from("a"));
+        assert_eq!(decomposition.y, String::from("a"));
+        assert_eq!(decomposition.z, String::from("bab"));
+        assert!(automata.verify_pumping(&decomposition, 0..=10));
+    }
+
+    #[test]
+    fn pumping_decomposition_not_applicable() {
+        let automata = make_automata_ends_with_ab();
+
+        assert_eq!(automata.pumping_decomposition("ab"), None);
+        assert_eq!(automata.pumping_decomposition("abba"), None);
+    }
+
+    #[test]
+    fn verify_pumping_fails_outside_language() {
+        let automata = make_automata_ends_with_ab();
+
+        let decomposition = PumpingDecomposition {
+            x: String::from("a"),
+            y: String::from("b"),
+            z: String::from(""),
+            state: String::from("q2"),
+        };
+
+        assert!(automata.verify_pumping(&decomposition, 1..2));
+        assert!(!automata.verify_pumping(&decomposition, 0..3));
+    }
+}
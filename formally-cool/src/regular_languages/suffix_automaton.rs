@@ -0,0 +1,239 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+struct SuffixState {
+    length: usize,
+    link: Option<usize>,
+    transitions: BTreeMap<char, usize>,
+    is_final: bool,
+}
+
+struct SuffixAutomaton {
+    nodes: Vec<SuffixState>,
+}
+
+impl SuffixAutomaton {
+    // Blumer et al. online construction, linear in the length of the text
+    fn new(text: &str) -> Self {
+        let mut automaton = SuffixAutomaton {
+            nodes: vec![SuffixState {
+                length: 0,
+                link: None,
+                transitions: BTreeMap::new(),
+                is_final: false,
+            }],
+        };
+
+        let mut last = 0;
+
+        for symbol in text.chars() {
+            let current = automaton.nodes.len();
+            automaton.nodes.push(SuffixState {
+                length: automaton.nodes[last].length + 1,
+                link: None,
+                transitions: BTreeMap::new(),
+                is_final: false,
+            });
+
+            let mut state = Some(last);
+            while let Some(actual_state) = state {
+                if automaton.nodes[actual_state]
+                    .transitions
+                    .contains_key(&symbol)
+                {
+                    break;
+                }
+                automaton.nodes[actual_state]
+                    .transitions
+                    .insert(symbol, current);
+                state = automaton.nodes[actual_state].link;
+            }
+
+            match state {
+                None => automaton.nodes[current].link = Some(0),
+                Some(state) => {
+                    let next = automaton.nodes[state].transitions[&symbol];
+
+                    if automaton.nodes[state].length + 1 == automaton.nodes[next].length {
+                        automaton.nodes[current].link = Some(next);
+                    } else {
+                        let clone = automaton.nodes.len();
+                        automaton.nodes.push(SuffixState {
+                            length: automaton.nodes[state].length + 1,
+                            link: automaton.nodes[next].link,
+                            transitions: automaton.nodes[next].transitions.clone(),
+                            is_final: false,
+                        });
+
+                        let mut redirect = Some(state);
+                        while let Some(actual_state) = redirect {
+                            if automaton.nodes[actual_state].transitions.get(&symbol) != Some(&next)
+                            {
+                                break;
+                            }
+                            automaton.nodes[actual_state]
+                                .transitions
+                                .insert(symbol, clone);
+                            redirect = automaton.nodes[actual_state].link;
+                        }
+
+                        automaton.nodes[next].link = Some(clone);
+                        automaton.nodes[current].link = Some(clone);
+                    }
+                }
+            }
+
+            last = current;
+        }
+
+        let mut terminal = Some(last);
+        while let Some(state) = terminal {
+            automaton.nodes[state].is_final = true;
+            terminal = automaton.nodes[state].link;
+        }
+
+        automaton
+    }
+
+    // the automaton is acyclic and every transition strictly increases the length, so visiting
+    // states from the longest down lets equal right languages be merged in a single pass
+    fn to_minimal_automata(&self) -> DeterministicFiniteAutomata {
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by(|left, right| self.nodes[*right].length.cmp(&self.nodes[*left].length));
+
+        let mut class = vec![0; self.nodes.len()];
+        let mut register: HashMap<(bool, Vec<(char, usize)>), usize> = HashMap::new();
+
+        for state in order {
+            let signature = (
+                self.nodes[state].is_final,
+                self.nodes[state]
+                    .transitions
+                    .iter()
+                    .map(|(symbol, out_state)| (*symbol, class[*out_state]))
+                    .collect(),
+            );
+
+            let next_class = register.len();
+            class[state] = *register.entry(signature).or_insert(next_class);
+        }
+
+        let mut names = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        let mut transition_function = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+        let mut alphabet = BTreeSet::new();
+
+        names.insert(class[0], String::from("q0"));
+        queue.push_back(0);
+
+        while let Some(state) = queue.pop_front() {
+            let name = names[&class[state]].clone();
+
+            if self.nodes[state].is_final {
+                accept_states.insert(name.clone());
+            }
+
+            for (symbol, out_state) in &self.nodes[state].transitions {
+                if !names.contains_key(&class[*out_state]) {
+                    names.insert(
+                        class[*out_state],
+                        String::from("q") + &names.len().to_string(),
+                    );
+                    queue.push_back(*out_state);
+                }
+
+                alphabet.insert(symbol.to_string());
+                transition_function.insert(
+                    (name.clone(), symbol.to_string()),
+                    names[&class[*out_state]].clone(),
+                );
+            }
+        }
+
+        DeterministicFiniteAutomata {
+            states: names.values().cloned().collect(),
+            alphabet,
+            transition_function,
+            start_state: String::from("q0"),
+            accept_states,
+        }
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    pub fn suffix_automaton(text: &str) -> Self {
+        SuffixAutomaton::new(text).to_minimal_automata()
+    }
+
+    pub fn substring_automaton(text: &str) -> Self {
+        let mut automaton = SuffixAutomaton::new(text);
+
+        for node in automaton.nodes.iter_mut() {
+            node.is_final = true;
+        }
+
+        automaton.to_minimal_automata()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn substrings(text: &str) -> BTreeSet<String> {
+        let symbols: Vec<char> = text.chars().collect();
+        let mut substrings = BTreeSet::new();
+
+        for start in 0..=symbols.len() {
+            for end in start..=symbols.len() {
+                substrings.insert(symbols[start..end].iter().collect());
+            }
+        }
+
+        substrings
+    }
+
+    #[test]
+    fn suffix_automaton() {
+        let text = "abcbc";
+
+        let automata = DeterministicFiniteAutomata::suffix_automaton(text);
+
+        let suffixes: Vec<String> = (0..=text.len()).map(|i| text[i..].to_string()).collect();
+
+        for substring in substrings(text) {
+            assert_eq!(automata.compute(&substring), suffixes.contains(&substring));
+        }
+        assert!(!automata.compute("abcbcb"));
+        assert!(!automata.compute("ca"));
+
+        assert_eq!(automata.states.len(), 8);
+        assert_eq!(
+            automata.myhill_nerode_report().classes.len(),
+            automata.states.len() + 1
+        );
+    }
+
+    #[test]
+    fn substring_automaton() {
+        for text in &["abcbc", "abbb", "aabbabd", "mississippi"] {
+            let automata = DeterministicFiniteAutomata::substring_automaton(text);
+
+            let mut expected = substrings(text);
+            expected.insert(String::new());
+
+            let mut accepted = automata.accepted_words().unwrap();
+            accepted.sort();
+
+            assert_eq!(accepted, expected.into_iter().collect::<Vec<_>>());
+            assert_eq!(
+                automata.myhill_nerode_report().classes.len(),
+                automata.states.len() + 1
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::hash_map::Entry;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+// distance to a merged pair, first letter to read and the pair it leads to
+type PairMerge = (usize, usize, (usize, usize));
+
+// automata up to this size get a shortest word from the subset search
+const EXACT_SEARCH_MAX_STATES: usize = 16;
+
+struct IndexedAutomata {
+    letters: Vec<String>,
+    transitions: Vec<Vec<usize>>,
+}
+
+impl IndexedAutomata {
+    fn new(automata: &DeterministicFiniteAutomata) -> Option<Self> {
+        let states: Vec<&String> = automata.states.iter().collect();
+        let letters: Vec<String> = automata.alphabet.iter().cloned().collect();
+        let mut transitions = vec![];
+
+        for state in &states {
+            let mut row = vec![];
+            for letter in &letters {
+                let out_state = automata
+                    .transition_function
+                    .get(&((*state).clone(), letter.clone()))?;
+                row.push(states.binary_search(&out_state).ok()?);
+            }
+            transitions.push(row);
+        }
+
+        Some(IndexedAutomata {
+            letters,
+            transitions,
+        })
+    }
+
+    fn shortest_synchronizing_word(&self) -> Option<String> {
+        let full_set: u64 = (1 << self.transitions.len()) - 1;
+
+        let mut parent: HashMap<u64, (u64, usize)> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        parent.insert(full_set, (full_set, 0));
+        queue.push_back(full_set);
+
+        while let Some(set) = queue.pop_front() {
+            if set.count_ones() == 1 {
+                let mut word = vec![];
+                let mut actual_set = set;
+
+                while actual_set != full_set {
+                    let (previous_set, letter) = parent[&actual_set];
+                    word.push(self.letters[letter].as_str());
+                    actual_set = previous_set;
+                }
+
+                word.reverse();
+
+                return Some(word.concat());
+            }
+
+            for letter in 0..self.letters.len() {
+                let mut out_set = 0;
+
+                for state in 0..self.transitions.len() {
+                    if set & (1 << state) != 0 {
+                        out_set |= 1 << self.transitions[state][letter];
+                    }
+                }
+
+                if let Entry::Vacant(entry) = parent.entry(out_set) {
+                    entry.insert((set, letter));
+                    queue.push_back(out_set);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn greedy_synchronizing_word(&self) -> Option<String> {
+        let size = self.transitions.len();
+
+        let mut preimages = vec![vec![vec![]; size]; self.letters.len()];
+        for (state, row) in self.transitions.iter().enumerate() {
+            for (letter, out_state) in row.iter().enumerate() {
+                preimages[letter][*out_state].push(state);
+            }
+        }
+
+        // backwards breadth first search over pairs, starting from the already merged ones
+        let mut merging: HashMap<(usize, usize), PairMerge> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        for state in 0..size {
+            queue.push_back((state, state));
+        }
+
+        while let Some((left, right)) = queue.pop_front() {
+            let distance = if left == right {
+                0
+            } else {
+                merging[&(left, right)].0
+            };
+
+            for (letter, letter_preimages) in preimages.iter().enumerate() {
+                for left_in in &letter_preimages[left] {
+                    for right_in in &letter_preimages[right] {
+                        let pair = if left_in < right_in {
+                            (*left_in, *right_in)
+                        } else {
+                            (*right_in, *left_in)
+                        };
+
+                        if pair.0 != pair.1 && !merging.contains_key(&pair) {
+                            merging.insert(pair, (distance + 1, letter, (left, right)));
+                            queue.push_back(pair);
+                        }
+                    }
+                }
+            }
+        }
+
+        if merging.len() != size * (size - 1) / 2 {
+            return None;
+        }
+
+        let mut actual_set: BTreeSet<usize> = (0..size).collect();
+        let mut word = String::new();
+
+        while actual_set.len() > 1 {
+            let mut best_pair = (0, 0);
+            let mut best_distance = usize::MAX;
+
+            for left in &actual_set {
+                for right in actual_set.range((left + 1)..) {
+                    let (distance, _, _) = merging[&(*left, *right)];
+                    if distance < best_distance {
+                        best_distance = distance;
+                        best_pair = (*left, *right);
+                    }
+                }
+            }
+
+            let mut pair = best_pair;
+
+            while pair.0 != pair.1 {
+                let (_, letter, next_pair) = merging[&pair];
+
+                word += &self.letters[letter];
+                actual_set = actual_set
+                    .iter()
+                    .map(|state| self.transitions[*state][letter])
+                    .collect();
+                pair = next_pair;
+            }
+        }
+
+        Some(word)
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    // a word leading every state to the same one; None when there is none, and also when the
+    // automata is partial or has a transition to an undeclared state, as synchronizing is only
+    // defined for complete automata
+    pub fn synchronizing_word(&self) -> Option<String> {
+        let automata = IndexedAutomata::new(self)?;
+
+        if self.states.len() <= 1 {
+            return Some(String::new());
+        }
+
+        if self.states.len() <= EXACT_SEARCH_MAX_STATES {
+            automata.shortest_synchronizing_word()
+        } else {
+            automata.greedy_synchronizing_word()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn make_cerny_automata(size: usize) -> DeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        for i in 0..size {
+            let state = String::from("q") + &i.to_string();
+            let next_state = String::from("q") + &((i + 1) % size).to_string();
+
+            hash.insert((state.clone(), String::from("a")), next_state);
+
+            if i == size - 1 {
+                hash.insert((state, String::from("b")), String::from("q0"));
+            } else {
+                hash.insert((state.clone(), String::from("b")), state);
+            }
+        }
+
+        DeterministicFiniteAutomata {
+            states: (0..size)
+                .map(|i| String::from("q") + &i.to_string())
+                .collect(),
+            alphabet: ["a".to_string(), "b".to_string()].iter().cloned().collect(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q0")].iter().cloned().collect(),
+        }
+    }
+
+    fn synchronizes(automata: &DeterministicFiniteAutomata, word: &str) -> bool {
+        let out_states: BTreeSet<String> = automata
+            .states
+            .iter()
+            .map(|state| {
+                word.chars().fold(state.clone(), |actual_state, symbol| {
+                    automata.transition_function[&(actual_state, symbol.to_string())].clone()
+                })
+            })
+            .collect();
+
+        out_states.len() == 1
+    }
+
+    #[test]
+    fn shortest_synchronizing_word() {
+        let automata = make_cerny_automata(4);
+
+        let word = automata.synchronizing_word().unwrap();
+
+        assert_eq!(word.len(), 9);
+        assert!(synchronizes(&automata, &word));
+    }
+
+    #[test]
+    fn greedy_synchronizing_word() {
+        let automata = make_cerny_automata(20);
+
+        let word = automata.synchronizing_word().unwrap();
+
+        assert!(word.len() >= 19 * 19);
+        assert!(synchronizes(&automata, &word));
+    }
+
+    #[test]
+    fn not_synchronizing() {
+        let mut automata = make_cerny_automata(4);
+
+        automata
+            .transition_function
+            .insert((String::from("q3"), String::from("b")), String::from("q3"));
+
+        assert_eq!(automata.synchronizing_word(), None);
+
+        let mut automata = make_cerny_automata(20);
+
+        automata.transition_function.insert(
+            (String::from("q19"), String::from("b")),
+            String::from("q19"),
+        );
+
+        assert_eq!(automata.synchronizing_word(), None);
+    }
+
+    #[test]
+    fn not_complete() {
+        let mut automata = make_cerny_automata(4);
+
+        automata
+            .transition_function
+            .remove(&(String::from("q0"), String::from("a")));
+
+        assert_eq!(automata.synchronizing_word(), None);
+
+        // completing it first is up to the caller, every state can then fall off through q0
+        let complete = automata.complete();
+        let word = complete.synchronizing_word().unwrap();
+
+        assert!(synchronizes(&complete, &word));
+
+        let mut automata = make_cerny_automata(4);
+
+        automata
+            .transition_function
+            .insert((String::from("q3"), String::from("b")), String::from("q4"));
+
+        assert_eq!(automata.synchronizing_word(), None);
+    }
+}
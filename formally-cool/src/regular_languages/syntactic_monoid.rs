@@ -0,0 +1,239 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonoidElement {
+    // shortest word inducing the element, the first one in alphabet order among those
+    pub representative: String,
+    // transformation[i] is the state reached from states[i] reading the representative
+    pub transformation: Vec<usize>,
+}
+
+// element 0 is the identity, induced by the empty word; multiplication[i][j] is the element
+// induced by reading the representative of i and then the one of j
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionMonoid {
+    pub states: Vec<String>,
+    pub elements: Vec<MonoidElement>,
+    pub multiplication: Vec<Vec<usize>>,
+}
+
+impl TransitionMonoid {
+    pub fn idempotents(&self) -> Vec<&MonoidElement> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.multiplication[*i][*i] == *i)
+            .map(|(_, element)| element)
+            .collect()
+    }
+
+    // the powers of every element end in a cycle, and the monoid is aperiodic when all those
+    // cycles have length one
+    pub fn is_aperiodic(&self) -> bool {
+        (0..self.elements.len()).all(|element| {
+            let mut powers = vec![element];
+            loop {
+                let last = *powers.last().unwrap();
+                let next = self.multiplication[last][element];
+
+                if next == last {
+                    return true;
+                }
+                if powers.contains(&next) {
+                    return false;
+                }
+                powers.push(next);
+            }
+        })
+    }
+}
+
+impl DeterministicFiniteAutomata {
+    // monoid of the transformations the words induce on the reachable states of the completed
+    // automata, explored breadth first from the identity
+    pub fn transition_monoid(&self) -> TransitionMonoid {
+        let automata = self.remove_unreachable_states().complete();
+
+        let states: Vec<String> = automata.states.iter().cloned().collect();
+        let letters: Vec<String> = automata.alphabet.iter().cloned().collect();
+
+        let index: BTreeMap<&String, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state, i))
+            .collect();
+
+        let letter_transformations: Vec<Vec<usize>> = letters
+            .iter()
+            .map(|letter| {
+                states
+                    .iter()
+                    .map(|state| {
+                        index[&automata.transition_function[&(state.clone(), letter.clone())]]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut elements = vec![MonoidElement {
+            representative: String::new(),
+            transformation: (0..states.len()).collect(),
+        }];
+        let mut element_index: HashMap<Vec<usize>, usize> = HashMap::new();
+        element_index.insert(elements[0].transformation.clone(), 0);
+
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+
+        while let Some(element) = queue.pop_front() {
+            for (letter, letter_transformation) in letters.iter().zip(&letter_transformations) {
+                let transformation: Vec<usize> = elements[element]
+                    .transformation
+                    .iter()
+                    .map(|state| letter_transformation[*state])
+                    .collect();
+
+                if !element_index.contains_key(&transformation) {
+                    element_index.insert(transformation.clone(), elements.len());
+                    queue.push_back(elements.len());
+                    elements.push(MonoidElement {
+                        representative: elements[element].representative.clone() + letter,
+                        transformation,
+                    });
+                }
+            }
+        }
+
+        let multiplication = elements
+            .iter()
+            .map(|left| {
+                elements
+                    .iter()
+                    .map(|right| {
+                        let product: Vec<usize> = left
+                            .transformation
+                            .iter()
+                            .map(|state| right.transformation[*state])
+                            .collect();
+                        element_index[&product]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        TransitionMonoid {
+            states,
+            elements,
+            multiplication,
+        }
+    }
+
+    pub fn syntactic_monoid(&self) -> TransitionMonoid {
+        self.minimize().transition_monoid()
+    }
+
+    // Schützenberger: a regular language is star free exactly when its syntactic monoid is
+    // aperiodic
+    pub fn is_star_free(&self) -> bool {
+        self.syntactic_monoid().is_aperiodic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::ab;
+
+    // (ab)*
+    fn make_automata_ab_star() -> DeterministicFiniteAutomata {
+        let mut hash = BTreeMap::new();
+
+        hash.insert((String::from("q0"), String::from("a")), String::from("q1"));
+        hash.insert((String::from("q1"), String::from("b")), String::from("q0"));
+
+        DeterministicFiniteAutomata {
+            states: [String::from("q0"), String::from("q1")]
+                .iter()
+                .cloned()
+                .collect(),
+            alphabet: ab(),
+            transition_function: hash,
+            start_state: String::from("q0"),
+            accept_states: [String::from("q0")].iter().cloned().collect(),
+        }
+    }
+
+    fn representatives<'a>(elements: impl IntoIterator<Item = &'a MonoidElement>) -> Vec<&'a str> {
+        elements
+            .into_iter()
+            .map(|element| element.representative.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn syntactic_monoid() {
+        let monoid = make_automata_ab_star().syntactic_monoid();
+
+        assert_eq!(
+            representatives(&monoid.elements),
+            vec!["", "a", "b", "aa", "ab", "ba"]
+        );
+        assert_eq!(
+            representatives(monoid.idempotents()),
+            vec!["", "aa", "ab", "ba"]
+        );
+
+        let element = |word: &str| {
+            monoid
+                .elements
+                .iter()
+                .position(|element| element.representative == word)
+                .unwrap()
+        };
+        assert_eq!(
+            monoid.multiplication[element("a")][element("b")],
+            element("ab")
+        );
+        assert_eq!(
+            monoid.multiplication[element("ab")][element("a")],
+            element("a")
+        );
+        assert_eq!(
+            monoid.multiplication[element("b")][element("b")],
+            element("aa")
+        );
+        assert_eq!(monoid.multiplication[0][element("ba")], element("ba"));
+
+        assert!(monoid.is_aperiodic());
+    }
+
+    #[test]
+    fn star_free() {
+        assert!(make_automata_ab_star().is_star_free());
+        assert!(catalog::contains("abb", &ab()).is_star_free());
+        assert!(catalog::ends_with("ab", &ab()).is_star_free());
+
+        assert!(!catalog::length_mod(2, 0, &ab()).unwrap().is_star_free());
+        assert!(!catalog::exactly_n_occurrences("a", 1, &ab())
+            .intersection(&catalog::length_mod(3, 1, &ab()).unwrap())
+            .is_star_free());
+    }
+
+    #[test]
+    fn transition_monoid_of_non_minimal_automata() {
+        let automata = catalog::divisible_by(3, 2).unwrap();
+        let doubled = automata.union(&automata);
+
+        let monoid = doubled.transition_monoid();
+        assert!(monoid.elements.len() >= doubled.syntactic_monoid().elements.len());
+        assert_eq!(
+            doubled.syntactic_monoid().elements.len(),
+            automata.syntactic_monoid().elements.len()
+        );
+        assert!(!automata.is_star_free());
+    }
+}
@@ -0,0 +1,332 @@
+use super::deterministic_finite_automata::DeterministicFiniteAutomata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+
+pub const LEFT_END_MARKER: &str = "⊢";
+pub const RIGHT_END_MARKER: &str = "⊣";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TwoWayOutcome {
+    Accept,
+    Reject,
+    Loop,
+}
+
+// the head starts on the left end marker of ⊢ w ⊣ and the word is accepted when it moves right
+// past the right end marker in an accept state; falling off the left end or a missing
+// transition rejects
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TwoWayDfa {
+    pub states: BTreeSet<String>,
+    pub alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String), (String, Direction)>,
+    pub start_state: String,
+    pub accept_states: BTreeSet<String>,
+}
+
+// what the machine does with a prefix ⊢ x of the tape: the state it first leaves the prefix
+// to the right in, coming from the start, and the same for every state it could come back in
+// from the right; None when it never leaves to the right
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct CrossingTable {
+    from_start: Option<usize>,
+    from_state: Vec<Option<usize>>,
+}
+
+struct IndexedTwoWayDfa {
+    // moves[state][symbol], the last two symbols being the end markers
+    moves: Vec<Vec<Option<(usize, Direction)>>>,
+    start: usize,
+    accepting: Vec<bool>,
+}
+
+impl IndexedTwoWayDfa {
+    fn new(automata: &TwoWayDfa) -> Self {
+        let states: Vec<&String> = automata.states.iter().collect();
+        let symbols: Vec<&str> = automata
+            .alphabet
+            .iter()
+            .map(String::as_str)
+            .chain([LEFT_END_MARKER, RIGHT_END_MARKER])
+            .collect();
+
+        let index = |state: &String| states.iter().position(|other| *other == state);
+
+        let moves = states
+            .iter()
+            .map(|state| {
+                symbols
+                    .iter()
+                    .map(|symbol| {
+                        let (out_state, direction) = automata
+                            .transition_function
+                            .get(&((*state).clone(), symbol.to_string()))?;
+                        Some((index(out_state)?, *direction))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        IndexedTwoWayDfa {
+            moves,
+            start: index(&automata.start_state).unwrap_or(states.len()),
+            accepting: states
+                .iter()
+                .map(|state| automata.accept_states.contains(*state))
+                .collect(),
+        }
+    }
+
+    fn left_end_marker(&self) -> usize {
+        self.moves.first().map_or(0, |row| row.len() - 2)
+    }
+
+    fn right_end_marker(&self) -> usize {
+        self.left_end_marker() + 1
+    }
+
+    // standing on the last cell of the prefix in the given state, where the head first moves
+    // past it; table describes the cells before it and is None for the left end marker
+    fn exit(&self, table: Option<&CrossingTable>, symbol: usize, state: usize) -> Option<usize> {
+        let mut visited = BTreeSet::new();
+        let mut state = state;
+
+        while visited.insert(state) {
+            let (out_state, direction) = (*self.moves.get(state)?.get(symbol)?)?;
+            match direction {
+                Direction::Right => return Some(out_state),
+                Direction::Left => state = table?.from_state[out_state]?,
+            }
+        }
+
+        None
+    }
+
+    fn append(&self, table: Option<&CrossingTable>, symbol: usize) -> CrossingTable {
+        let from_start = match table {
+            Some(table) => table.from_start,
+            None => Some(self.start),
+        };
+
+        CrossingTable {
+            from_start: from_start.and_then(|state| self.exit(table, symbol, state)),
+            from_state: (0..self.moves.len())
+                .map(|state| self.exit(table, symbol, state))
+                .collect(),
+        }
+    }
+
+    fn is_accepting(&self, table: &CrossingTable) -> bool {
+        match self.append(Some(table), self.right_end_marker()).from_start {
+            Some(state) => self.accepting[state],
+            None => false,
+        }
+    }
+}
+
+impl TwoWayDfa {
+    // a configuration repeating means the machine runs forever
+    pub fn simulate(&self, input: &str) -> TwoWayOutcome {
+        let tape: Vec<String> = Some(LEFT_END_MARKER.to_string())
+            .into_iter()
+            .chain(input.chars().map(|symbol| symbol.to_string()))
+            .chain(Some(RIGHT_END_MARKER.to_string()))
+            .collect();
+
+        let mut visited = BTreeSet::new();
+        let mut state = self.start_state.clone();
+        let mut position = 0;
+
+        while visited.insert((state.clone(), position)) {
+            let (out_state, direction) = match self
+                .transition_function
+                .get(&(state, tape[position].clone()))
+            {
+                Some(transition) => transition.clone(),
+                None => return TwoWayOutcome::Reject,
+            };
+            state = out_state;
+
+            match direction {
+                Direction::Left if position == 0 => return TwoWayOutcome::Reject,
+                Direction::Left => position -= 1,
+                Direction::Right if position + 1 == tape.len() => {
+                    return match self.accept_states.contains(&state) {
+                        true => TwoWayOutcome::Accept,
+                        false => TwoWayOutcome::Reject,
+                    };
+                }
+                Direction::Right => position += 1,
+            }
+        }
+
+        TwoWayOutcome::Loop
+    }
+
+    pub fn compute(&self, input: &str) -> bool {
+        self.simulate(input) == TwoWayOutcome::Accept
+    }
+}
+
+// shepherdson: the crossing table of the prefix read so far is all a one way automaton needs
+// to remember, and appending a letter updates it without looking back at the prefix
+impl From<&TwoWayDfa> for DeterministicFiniteAutomata {
+    fn from(automata: &TwoWayDfa) -> Self {
+        let indexed = IndexedTwoWayDfa::new(automata);
+        let letters: Vec<&String> = automata.alphabet.iter().collect();
+
+        let start_table = indexed.append(None, indexed.left_end_marker());
+
+        let mut tables = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        let mut transition_function = BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        tables.insert(start_table.clone(), String::from("q0"));
+        queue.push_back(start_table);
+
+        while let Some(table) = queue.pop_front() {
+            let state = tables[&table].clone();
+            if indexed.is_accepting(&table) {
+                accept_states.insert(state.clone());
+            }
+
+            for (symbol, letter) in letters.iter().enumerate() {
+                let next = indexed.append(Some(&table), symbol);
+
+                if !tables.contains_key(&next) {
+                    let name = String::from("q") + &tables.len().to_string();
+                    tables.insert(next.clone(), name);
+                    queue.push_back(next.clone());
+                }
+
+                transition_function
+                    .insert((state.clone(), (*letter).clone()), tables[&next].clone());
+            }
+        }
+
+        DeterministicFiniteAutomata {
+            states: tables.values().cloned().collect(),
+            alphabet: automata.alphabet.clone(),
+            transition_function,
+            start_state: String::from("q0"),
+            accept_states,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::words;
+
+    fn make_automata(
+        start_state: &str,
+        accept_states: &[&str],
+        transitions: &[(&str, &str, &str, Direction)],
+    ) -> TwoWayDfa {
+        let mut automata = TwoWayDfa {
+            states: BTreeSet::new(),
+            alphabet: ["a", "b"].iter().map(|x| x.to_string()).collect(),
+            transition_function: BTreeMap::new(),
+            start_state: start_state.to_string(),
+            accept_states: accept_states.iter().map(|x| x.to_string()).collect(),
+        };
+
+        for (state, symbols, out_state, direction) in transitions {
+            for symbol in symbols.split(',') {
+                automata.transition_function.insert(
+                    (state.to_string(), symbol.to_string()),
+                    (out_state.to_string(), *direction),
+                );
+            }
+            automata.states.insert(state.to_string());
+            automata.states.insert(out_state.to_string());
+        }
+
+        automata
+    }
+
+    // runs to the right end marker, walks back three letters and carries the answer out
+    fn make_automata_third_from_last_a() -> TwoWayDfa {
+        make_automata(
+            "scan",
+            &["yes"],
+            &[
+                ("scan", "⊢,a,b", "scan", Direction::Right),
+                ("scan", "⊣", "back1", Direction::Left),
+                ("back1", "a,b", "back2", Direction::Left),
+                ("back2", "a,b", "back3", Direction::Left),
+                ("back3", "a", "yes", Direction::Right),
+                ("back3", "b", "no", Direction::Right),
+                ("yes", "a,b,⊣", "yes", Direction::Right),
+                ("no", "a,b,⊣", "no", Direction::Right),
+            ],
+        )
+    }
+
+    // an a sends the head one cell back and on to the same a again, forever
+    fn make_automata_bouncing() -> TwoWayDfa {
+        make_automata(
+            "s",
+            &["s"],
+            &[
+                ("s", "⊢,b,⊣", "s", Direction::Right),
+                ("s", "a", "t", Direction::Left),
+                ("t", "⊢,a,b", "s", Direction::Right),
+            ],
+        )
+    }
+
+    #[test]
+    fn simulate() {
+        let automata = make_automata_third_from_last_a();
+
+        assert_eq!(automata.simulate("abb"), TwoWayOutcome::Accept);
+        assert_eq!(automata.simulate("bbabb"), TwoWayOutcome::Accept);
+        assert_eq!(automata.simulate("bab"), TwoWayOutcome::Reject);
+        // the walk back falls off the left end
+        assert_eq!(automata.simulate("ab"), TwoWayOutcome::Reject);
+
+        let bouncing = make_automata_bouncing();
+
+        assert_eq!(bouncing.simulate("bbb"), TwoWayOutcome::Accept);
+        assert_eq!(bouncing.simulate("bab"), TwoWayOutcome::Loop);
+        assert_eq!(bouncing.simulate("c"), TwoWayOutcome::Reject);
+    }
+
+    #[test]
+    fn to_dfa() {
+        let alphabet = ["a", "b"].iter().map(|x| x.to_string()).collect();
+
+        let automata = make_automata_third_from_last_a();
+        let dfa = DeterministicFiniteAutomata::from(&automata);
+
+        for word in words(&["a", "b"], 8) {
+            assert_eq!(dfa.compute(&word), automata.compute(&word));
+        }
+
+        let expected = catalog::ends_with("aaa", &alphabet)
+            .union(&catalog::ends_with("aab", &alphabet))
+            .union(&catalog::ends_with("aba", &alphabet))
+            .union(&catalog::ends_with("abb", &alphabet));
+        assert_eq!(dfa.find_counterexample(&expected), None);
+
+        let bouncing = make_automata_bouncing();
+        let dfa = DeterministicFiniteAutomata::from(&bouncing);
+
+        for word in words(&["a", "b"], 8) {
+            assert_eq!(dfa.compute(&word), bouncing.compute(&word));
+        }
+        assert_eq!(dfa.minimize().states.len(), 1);
+    }
+}
@@ -0,0 +1,420 @@
+use super::nondeterministic_finite_automata::NondeterministicFiniteAutomata;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+// star is the sum of all the powers of a weight, which lets cycles be summed in closed form
+pub trait Semiring: Clone + PartialEq + Debug {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn plus(&self, other: &Self) -> Self;
+    fn times(&self, other: &Self) -> Self;
+    fn star(&self) -> Self;
+}
+
+// whether some path exists
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Boolean(pub bool);
+
+// cost of the cheapest path, infinite when there is none
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Tropical(pub f64);
+
+// total probability of the paths
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Probability(pub f64);
+
+// number of paths, saturating at u64::MAX which also stands for infinitely many
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Counting(pub u64);
+
+impl Semiring for Boolean {
+    fn zero() -> Self {
+        Boolean(false)
+    }
+    fn one() -> Self {
+        Boolean(true)
+    }
+    fn plus(&self, other: &Self) -> Self {
+        Boolean(self.0 || other.0)
+    }
+    fn times(&self, other: &Self) -> Self {
+        Boolean(self.0 && other.0)
+    }
+    fn star(&self) -> Self {
+        Boolean(true)
+    }
+}
+
+impl Semiring for Tropical {
+    fn zero() -> Self {
+        Tropical(f64::INFINITY)
+    }
+    fn one() -> Self {
+        Tropical(0.0)
+    }
+    fn plus(&self, other: &Self) -> Self {
+        Tropical(self.0.min(other.0))
+    }
+    fn times(&self, other: &Self) -> Self {
+        Tropical(self.0 + other.0)
+    }
+    // a negative cycle can be taken forever
+    fn star(&self) -> Self {
+        if self.0 < 0.0 {
+            Tropical(f64::NEG_INFINITY)
+        } else {
+            Tropical(0.0)
+        }
+    }
+}
+
+impl Semiring for Probability {
+    fn zero() -> Self {
+        Probability(0.0)
+    }
+    fn one() -> Self {
+        Probability(1.0)
+    }
+    fn plus(&self, other: &Self) -> Self {
+        Probability(self.0 + other.0)
+    }
+    fn times(&self, other: &Self) -> Self {
+        Probability(self.0 * other.0)
+    }
+    // geometric series, which diverges from 1 on
+    fn star(&self) -> Self {
+        if self.0 < 1.0 {
+            Probability(1.0 / (1.0 - self.0))
+        } else {
+            Probability(f64::INFINITY)
+        }
+    }
+}
+
+impl Semiring for Counting {
+    fn zero() -> Self {
+        Counting(0)
+    }
+    fn one() -> Self {
+        Counting(1)
+    }
+    fn plus(&self, other: &Self) -> Self {
+        Counting(self.0.saturating_add(other.0))
+    }
+    fn times(&self, other: &Self) -> Self {
+        Counting(self.0.saturating_mul(other.0))
+    }
+    fn star(&self) -> Self {
+        if self.0 == 0 {
+            Counting(1)
+        } else {
+            Counting(u64::MAX)
+        }
+    }
+}
+
+// a path weighs the product of its initial, transition and final weights and a word the sum
+// over its paths; there are no epsilon transitions and missing weights are zero
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WeightedAutomaton<S: Semiring> {
+    pub states: BTreeSet<String>,
+    pub alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String), BTreeMap<String, S>>,
+    pub initial_weights: BTreeMap<String, S>,
+    pub final_weights: BTreeMap<String, S>,
+}
+
+fn add_weight<S: Semiring>(weights: &mut BTreeMap<String, S>, state: &str, weight: S) {
+    let sum = match weights.get(state) {
+        Some(old_weight) => old_weight.plus(&weight),
+        None => weight,
+    };
+    weights.insert(state.to_string(), sum);
+}
+
+impl<S: Semiring> WeightedAutomaton<S> {
+    fn final_weight(&self, weights: &BTreeMap<String, S>) -> S {
+        weights
+            .iter()
+            .filter_map(|(state, weight)| {
+                self.final_weights
+                    .get(state)
+                    .map(|final_weight| weight.times(final_weight))
+            })
+            .fold(S::zero(), |sum, weight| sum.plus(&weight))
+    }
+
+    fn step(&self, weights: &BTreeMap<String, S>, letter: &str) -> BTreeMap<String, S> {
+        let mut next = BTreeMap::new();
+
+        for (state, weight) in weights {
+            if let Some(targets) = self
+                .transition_function
+                .get(&(state.clone(), letter.to_string()))
+            {
+                for (target, transition_weight) in targets {
+                    add_weight(&mut next, target, weight.times(transition_weight));
+                }
+            }
+        }
+
+        next
+    }
+
+    pub fn weight(&self, input: &str) -> S {
+        let mut weights = self.initial_weights.clone();
+
+        for symbol in input.chars() {
+            weights = self.step(&weights, &symbol.to_string());
+        }
+
+        self.final_weight(&weights)
+    }
+
+    // for every state the sum over the paths reaching it from the initial weights; the matrix
+    // of letter summed weights is closed under star one state at a time, as in floyd warshall,
+    // so cycles are summed by star instead of unrolled
+    pub fn shortest_distance(&self) -> BTreeMap<String, S> {
+        let mut states = self.states.clone();
+        states.extend(self.initial_weights.keys().cloned());
+        for ((state, _), targets) in &self.transition_function {
+            states.insert(state.clone());
+            states.extend(targets.keys().cloned());
+        }
+        let states: Vec<String> = states.into_iter().collect();
+        let index: BTreeMap<&String, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (state, i))
+            .collect();
+
+        let size = states.len();
+        let mut closure = vec![vec![S::zero(); size]; size];
+        for ((state, letter), targets) in &self.transition_function {
+            if !self.alphabet.contains(letter) {
+                continue;
+            }
+            for (target, weight) in targets {
+                let entry = &mut closure[index[state]][index[target]];
+                *entry = entry.plus(weight);
+            }
+        }
+
+        for k in 0..size {
+            let loop_star = closure[k][k].star();
+            let previous = closure.clone();
+            for i in 0..size {
+                // paths through k need both edges; skipping a zero one also keeps zero times an
+                // infinite star from turning into a nan
+                if previous[i][k] == S::zero() {
+                    continue;
+                }
+                for j in 0..size {
+                    if previous[k][j] == S::zero() {
+                        continue;
+                    }
+                    let through = previous[i][k].times(&loop_star).times(&previous[k][j]);
+                    closure[i][j] = previous[i][j].plus(&through);
+                }
+            }
+        }
+        for (i, row) in closure.iter_mut().enumerate() {
+            row[i] = row[i].plus(&S::one());
+        }
+
+        let mut distances = BTreeMap::new();
+        for (state, weight) in &self.initial_weights {
+            for (target, target_weight) in closure[index[state]].iter().enumerate() {
+                if *target_weight != S::zero() {
+                    add_weight(&mut distances, &states[target], weight.times(target_weight));
+                }
+            }
+        }
+
+        distances
+    }
+
+    // sum of the weights of all words
+    pub fn total_weight(&self) -> S {
+        self.final_weight(&self.shortest_distance())
+    }
+}
+
+// every path weighs one, so the boolean semiring gives back the language and the counting one
+// the number of accepting paths, once epsilon transitions are removed
+impl<S: Semiring> From<&NondeterministicFiniteAutomata> for WeightedAutomaton<S> {
+    fn from(automata: &NondeterministicFiniteAutomata) -> Self {
+        let automata = automata.remove_epsilon_transitions();
+
+        let weigh = |states: &BTreeSet<String>| -> BTreeMap<String, S> {
+            states
+                .iter()
+                .map(|state| (state.clone(), S::one()))
+                .collect()
+        };
+
+        WeightedAutomaton {
+            states: automata.states.clone(),
+            alphabet: automata.alphabet.clone(),
+            transition_function: automata
+                .transition_function
+                .iter()
+                .map(|(key, targets)| (key.clone(), weigh(targets)))
+                .collect(),
+            initial_weights: weigh(&[automata.start_state.clone()].iter().cloned().collect()),
+            final_weights: weigh(&automata.accept_states),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::catalog;
+    use crate::regular_languages::fixtures::{ab, renamed};
+    use crate::regular_languages::DeterministicFiniteAutomata;
+
+    fn make_automaton<S: Semiring>(
+        initial: &[(&str, S)],
+        transitions: &[(&str, &str, &str, S)],
+        finals: &[(&str, S)],
+    ) -> WeightedAutomaton<S> {
+        let mut automaton = WeightedAutomaton {
+            states: BTreeSet::new(),
+            alphabet: BTreeSet::new(),
+            transition_function: BTreeMap::new(),
+            initial_weights: BTreeMap::new(),
+            final_weights: BTreeMap::new(),
+        };
+
+        for (state, letter, target, weight) in transitions {
+            automaton
+                .transition_function
+                .entry((state.to_string(), letter.to_string()))
+                .or_default()
+                .insert(target.to_string(), weight.clone());
+            automaton.states.insert(state.to_string());
+            automaton.states.insert(target.to_string());
+            automaton.alphabet.insert(letter.to_string());
+        }
+        for (state, weight) in initial {
+            automaton
+                .initial_weights
+                .insert(state.to_string(), weight.clone());
+        }
+        for (state, weight) in finals {
+            automaton
+                .final_weights
+                .insert(state.to_string(), weight.clone());
+        }
+
+        automaton
+    }
+
+    #[test]
+    fn boolean() {
+        let nfa = NondeterministicFiniteAutomata::damerau_levenshtein_automaton("abba", 1, &ab());
+        let automaton = WeightedAutomaton::<Boolean>::from(&nfa);
+        let simulator = nfa.simulator().unwrap();
+
+        for word in &["abba", "aba", "baba", "abab", "bbba", "ab", "", "aabba"] {
+            assert_eq!(automaton.weight(word), Boolean(simulator.compute(word)));
+        }
+        assert_eq!(automaton.total_weight(), Boolean(true));
+    }
+
+    #[test]
+    fn tropical() {
+        // reading a costs 1 at p and 3 at q, b is free except when it moves from p to the final
+        // state q, which costs 2
+        let automaton = make_automaton(
+            &[("p", Tropical(0.0))],
+            &[
+                ("p", "a", "p", Tropical(1.0)),
+                ("p", "b", "q", Tropical(2.0)),
+                ("p", "b", "p", Tropical(0.0)),
+                ("q", "a", "q", Tropical(3.0)),
+                ("q", "b", "q", Tropical(0.0)),
+            ],
+            &[("q", Tropical(0.5))],
+        );
+
+        assert_eq!(automaton.weight("aab"), Tropical(4.5));
+        assert_eq!(automaton.weight("abba"), Tropical(6.5));
+        assert_eq!(automaton.weight("aa"), Tropical(f64::INFINITY));
+
+        let distances = automaton.shortest_distance();
+        assert_eq!(distances["p"], Tropical(0.0));
+        assert_eq!(distances["q"], Tropical(2.0));
+        assert_eq!(automaton.total_weight(), Tropical(2.5));
+    }
+
+    #[test]
+    fn probability() {
+        let automaton = make_automaton(
+            &[("s", Probability(1.0))],
+            &[
+                ("s", "a", "s", Probability(0.5)),
+                ("s", "a", "t", Probability(0.25)),
+                ("s", "b", "t", Probability(0.25)),
+                ("t", "b", "u", Probability(1.0)),
+            ],
+            &[("u", Probability(1.0))],
+        );
+
+        assert_eq!(automaton.weight("ab"), Probability(0.25));
+        assert_eq!(automaton.weight("aab"), Probability(0.125));
+        assert_eq!(automaton.weight("ba"), Probability(0.0));
+
+        // the a loop at s sums to 1 / (1 - 0.5)
+        let distances = automaton.shortest_distance();
+        assert_eq!(distances["s"], Probability(2.0));
+        assert_eq!(distances["t"], Probability(1.0));
+        assert_eq!(automaton.total_weight(), Probability(1.0));
+
+        // t never stops looping, so only the empty word at s is accepted
+        let absorbing = make_automaton(
+            &[("s", Probability(1.0))],
+            &[
+                ("s", "a", "t", Probability(0.5)),
+                ("t", "a", "t", Probability(1.0)),
+            ],
+            &[("s", Probability(1.0))],
+        );
+        let distances = absorbing.shortest_distance();
+        assert_eq!(distances["s"], Probability(1.0));
+        assert_eq!(distances["t"], Probability(f64::INFINITY));
+        assert_eq!(absorbing.total_weight(), Probability(1.0));
+    }
+
+    #[test]
+    fn counting() {
+        let left = DeterministicFiniteAutomata::from_sorted_words(["a", "ab", "b"]).unwrap();
+        let right = DeterministicFiniteAutomata::from_sorted_words(["ab", "ba"]).unwrap();
+
+        let right = renamed(&NondeterministicFiniteAutomata::from(&right), "r");
+
+        let union = NondeterministicFiniteAutomata::from(&left).union(&right);
+        let automaton = WeightedAutomaton::<Counting>::from(&union);
+
+        assert_eq!(automaton.weight("ab"), Counting(2));
+        assert_eq!(automaton.weight("ba"), Counting(1));
+        assert_eq!(automaton.weight("bb"), Counting(0));
+        assert_eq!(automaton.total_weight(), Counting(5));
+
+        // a cycle gives infinitely many words, and long words more paths than fit
+        let looping = WeightedAutomaton::<Counting>::from(&NondeterministicFiniteAutomata::from(
+            &catalog::sigma_star(&ab()),
+        ));
+        assert_eq!(looping.total_weight(), Counting(u64::MAX));
+
+        let doubling = make_automaton(
+            &[("s", Counting(1))],
+            &[("s", "a", "s", Counting(2))],
+            &[("s", Counting(1))],
+        );
+        assert_eq!(doubling.weight(&"a".repeat(70)), Counting(u64::MAX));
+    }
+}
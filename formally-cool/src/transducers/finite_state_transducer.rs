@@ -0,0 +1,558 @@
+use crate::regular_languages::NondeterministicFiniteAutomata;
+use crate::regular_languages::{fresh_state, make_cartesian_state};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::VecDeque;
+
+// transitions are keyed by (state, input, output), where either side may be the empty word "&"
+// or a word of several symbols; the operations below work on split_labels, where no side holds
+// more than one symbol
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FiniteStateTransducer {
+    pub states: BTreeSet<String>,
+    pub input_alphabet: BTreeSet<String>,
+    pub output_alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String, String), BTreeSet<String>>,
+    pub start_state: String,
+    pub accept_states: BTreeSet<String>,
+}
+
+type Node = (String, usize);
+
+// the transducer run against a fixed input: a node is a state and how much of the input has
+// been read, and &:& moves are folded into the edges through epsilon closures
+struct InputProduct<'a> {
+    transducer: &'a FiniteStateTransducer,
+    symbols: Vec<String>,
+    epsilon_closures: BTreeMap<String, BTreeSet<String>>,
+    productive: BTreeSet<Node>,
+}
+
+impl<'a> InputProduct<'a> {
+    fn closure(&self, state: &str) -> BTreeSet<String> {
+        match self.epsilon_closures.get(state) {
+            Some(closure) => closure.clone(),
+            None => [state.to_string()].iter().cloned().collect(),
+        }
+    }
+
+    fn is_final(&self, (state, position): &Node) -> bool {
+        *position == self.symbols.len()
+            && !self
+                .closure(state)
+                .is_disjoint(&self.transducer.accept_states)
+    }
+
+    fn edges(&self, (state, position): &Node) -> Vec<(String, Node)> {
+        let mut edges = vec![];
+
+        for reached in self.closure(state) {
+            for ((from, input, output), targets) in &self.transducer.transition_function {
+                if *from != reached || (input == "&" && output == "&") {
+                    continue;
+                }
+
+                let next_position = if input == "&" {
+                    *position
+                } else if self.symbols.get(*position) == Some(input) {
+                    position + 1
+                } else {
+                    continue;
+                };
+                let output = if output == "&" { "" } else { output };
+
+                for target in targets {
+                    edges.push((output.to_string(), (target.clone(), next_position)));
+                }
+            }
+        }
+
+        edges
+    }
+
+    fn find_productive(&mut self) {
+        let mut reachable = BTreeSet::new();
+        let mut stack = vec![(self.transducer.start_state.clone(), 0)];
+
+        while let Some(node) = stack.pop() {
+            if reachable.insert(node.clone()) {
+                stack.extend(self.edges(&node).into_iter().map(|(_, target)| target));
+            }
+        }
+
+        let mut productive: BTreeSet<Node> = reachable
+            .iter()
+            .filter(|node| self.is_final(node))
+            .cloned()
+            .collect();
+
+        let mut stop = false;
+        while !stop {
+            stop = true;
+            for node in &reachable {
+                if !productive.contains(node)
+                    && self
+                        .edges(node)
+                        .iter()
+                        .any(|(_, target)| productive.contains(target))
+                {
+                    productive.insert(node.clone());
+                    stop = false;
+                }
+            }
+        }
+
+        self.productive = productive;
+    }
+
+    // outputs written from node until the input is consumed in an accept state; a cycle through
+    // productive nodes that does not read input means there are infinitely many of them
+    fn suffixes(
+        &self,
+        node: &Node,
+        memo: &mut BTreeMap<Node, BTreeSet<String>>,
+        on_stack: &mut BTreeSet<Node>,
+    ) -> Option<BTreeSet<String>> {
+        if let Some(suffixes) = memo.get(node) {
+            return Some(suffixes.clone());
+        }
+        if !on_stack.insert(node.clone()) {
+            return None;
+        }
+
+        let mut suffixes = BTreeSet::new();
+        if self.is_final(node) {
+            suffixes.insert(String::new());
+        }
+
+        for (output, target) in self.edges(node) {
+            if !self.productive.contains(&target) {
+                continue;
+            }
+            for suffix in self.suffixes(&target, memo, on_stack)? {
+                suffixes.insert(output.clone() + &suffix);
+            }
+        }
+
+        on_stack.remove(node);
+        memo.insert(node.clone(), suffixes.clone());
+
+        Some(suffixes)
+    }
+}
+
+impl FiniteStateTransducer {
+    // closures under the &:& transitions, which read and write nothing
+    fn epsilon_closures(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut transition_function = BTreeMap::new();
+
+        for ((state, input, output), targets) in &self.transition_function {
+            if input == "&" && output == "&" {
+                transition_function.insert((state.clone(), String::from("&")), targets.clone());
+            }
+        }
+
+        NondeterministicFiniteAutomata {
+            states: self.states.clone(),
+            alphabet: BTreeSet::new(),
+            transition_function,
+            start_state: self.start_state.clone(),
+            accept_states: self.accept_states.clone(),
+        }
+        .epsilon_closures()
+    }
+
+    // a transition whose input or output has several symbols becomes a chain through fresh
+    // states, reading and writing one symbol per step and padding the shorter side with "&"
+    pub fn split_labels(&self) -> Self {
+        let symbols = |label: &String| -> Vec<String> {
+            if label == "&" {
+                vec![]
+            } else {
+                label.chars().map(|symbol| symbol.to_string()).collect()
+            }
+        };
+        let split_alphabet = |alphabet: &BTreeSet<String>| -> BTreeSet<String> {
+            alphabet.iter().flat_map(symbols).collect()
+        };
+
+        // fresh names avoid the states the transitions mention even when they are undeclared
+        let mut taken = self.states.clone();
+        for ((state, _, _), targets) in &self.transition_function {
+            taken.insert(state.clone());
+            taken.extend(targets.iter().cloned());
+        }
+
+        let mut states = self.states.clone();
+        let mut transition_function: BTreeMap<(String, String, String), BTreeSet<String>> =
+            BTreeMap::new();
+
+        for ((state, input, output), targets) in &self.transition_function {
+            let inputs = symbols(input);
+            let outputs = symbols(output);
+            let length = inputs.len().max(outputs.len());
+
+            let mut from = state.clone();
+            for step in 0..length.max(1) {
+                let (input, output) = if length <= 1 {
+                    (input.clone(), output.clone())
+                } else {
+                    let pad = || String::from("&");
+                    (
+                        inputs.get(step).cloned().unwrap_or_else(pad),
+                        outputs.get(step).cloned().unwrap_or_else(pad),
+                    )
+                };
+
+                let entry = transition_function
+                    .entry((from.clone(), input, output))
+                    .or_default();
+                if step + 1 >= length {
+                    entry.extend(targets.iter().cloned());
+                    continue;
+                }
+
+                from = fresh_state("q", &taken);
+                taken.insert(from.clone());
+                states.insert(from.clone());
+                entry.insert(from.clone());
+            }
+        }
+
+        FiniteStateTransducer {
+            states,
+            input_alphabet: split_alphabet(&self.input_alphabet),
+            output_alphabet: split_alphabet(&self.output_alphabet),
+            transition_function,
+            start_state: self.start_state.clone(),
+            accept_states: self.accept_states.clone(),
+        }
+    }
+
+    // every output the input can be rewritten into, or None when there are infinitely many
+    pub fn apply(&self, input: &str) -> Option<BTreeSet<String>> {
+        let transducer = self.split_labels();
+        let mut product = InputProduct {
+            transducer: &transducer,
+            symbols: input.chars().map(|symbol| symbol.to_string()).collect(),
+            epsilon_closures: transducer.epsilon_closures(),
+            productive: BTreeSet::new(),
+        };
+        product.find_productive();
+
+        let start = (self.start_state.clone(), 0);
+        if !product.productive.contains(&start) {
+            return Some(BTreeSet::new());
+        }
+
+        product.suffixes(&start, &mut BTreeMap::new(), &mut BTreeSet::new())
+    }
+
+    // reads with self and feeds what self writes to other; a pair moves alone when self writes
+    // nothing or other reads nothing
+    pub fn compose(&self, other: &Self) -> Self {
+        let left_transducer = self.split_labels();
+        let right_transducer = other.split_labels();
+        let (this, other) = (&left_transducer, &right_transducer);
+
+        let start_state = make_cartesian_state(&this.start_state, &other.start_state);
+
+        let mut states = BTreeSet::new();
+        let mut transition_function: BTreeMap<(String, String, String), BTreeSet<String>> =
+            BTreeMap::new();
+        let mut accept_states = BTreeSet::new();
+
+        let mut queue = VecDeque::new();
+        queue.push_back((this.start_state.clone(), other.start_state.clone()));
+
+        while let Some((left, right)) = queue.pop_front() {
+            let pair = make_cartesian_state(&left, &right);
+            if !states.insert(pair.clone()) {
+                continue;
+            }
+            if this.accept_states.contains(&left) && other.accept_states.contains(&right) {
+                accept_states.insert(pair.clone());
+            }
+
+            let mut moves = vec![];
+
+            for ((state, input, middle), left_targets) in &this.transition_function {
+                if *state != left {
+                    continue;
+                }
+
+                if middle == "&" {
+                    for left_target in left_targets {
+                        moves.push((input, middle, left_target, &right));
+                    }
+                    continue;
+                }
+
+                for ((other_state, other_input, output), right_targets) in
+                    &other.transition_function
+                {
+                    if *other_state == right && other_input == middle {
+                        for left_target in left_targets {
+                            for right_target in right_targets {
+                                moves.push((input, output, left_target, right_target));
+                            }
+                        }
+                    }
+                }
+            }
+
+            for ((state, input, output), right_targets) in &other.transition_function {
+                if *state == right && input == "&" {
+                    for right_target in right_targets {
+                        moves.push((input, output, &left, right_target));
+                    }
+                }
+            }
+
+            for (input, output, left_target, right_target) in moves {
+                transition_function
+                    .entry((pair.clone(), input.clone(), output.clone()))
+                    .or_default()
+                    .insert(make_cartesian_state(left_target, right_target));
+                queue.push_back((left_target.clone(), right_target.clone()));
+            }
+        }
+
+        FiniteStateTransducer {
+            states,
+            input_alphabet: this.input_alphabet.clone(),
+            output_alphabet: other.output_alphabet.clone(),
+            transition_function,
+            start_state,
+            accept_states,
+        }
+    }
+
+    pub fn invert(&self) -> Self {
+        FiniteStateTransducer {
+            states: self.states.clone(),
+            input_alphabet: self.output_alphabet.clone(),
+            output_alphabet: self.input_alphabet.clone(),
+            transition_function: self
+                .transition_function
+                .iter()
+                .map(|((state, input, output), targets)| {
+                    (
+                        (state.clone(), output.clone(), input.clone()),
+                        targets.clone(),
+                    )
+                })
+                .collect(),
+            start_state: self.start_state.clone(),
+            accept_states: self.accept_states.clone(),
+        }
+    }
+
+    // the inputs that have some output
+    pub fn input_projection(&self) -> NondeterministicFiniteAutomata {
+        self.projection(
+            |transducer| &transducer.input_alphabet,
+            |input, _| input.clone(),
+        )
+    }
+
+    // the outputs of some input
+    pub fn output_projection(&self) -> NondeterministicFiniteAutomata {
+        self.projection(
+            |transducer| &transducer.output_alphabet,
+            |_, output| output.clone(),
+        )
+    }
+
+    fn projection(
+        &self,
+        alphabet: impl Fn(&Self) -> &BTreeSet<String>,
+        side: impl Fn(&String, &String) -> String,
+    ) -> NondeterministicFiniteAutomata {
+        let transducer = self.split_labels();
+        let mut transition_function: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+
+        for ((state, input, output), targets) in &transducer.transition_function {
+            transition_function
+                .entry((state.clone(), side(input, output)))
+                .or_default()
+                .extend(targets.iter().cloned());
+        }
+
+        NondeterministicFiniteAutomata {
+            alphabet: alphabet(&transducer).clone(),
+            states: transducer.states,
+            transition_function,
+            start_state: self.start_state.clone(),
+            accept_states: self.accept_states.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::set;
+
+    fn make_transducer(
+        start_state: &str,
+        accept_states: &[&str],
+        transitions: &[(&str, &str, &str, &str)],
+    ) -> FiniteStateTransducer {
+        let mut transition_function: BTreeMap<(String, String, String), BTreeSet<String>> =
+            BTreeMap::new();
+        let mut states = set(&[start_state]);
+        let mut input_alphabet = BTreeSet::new();
+        let mut output_alphabet = BTreeSet::new();
+
+        for (state, input, output, target) in transitions {
+            transition_function
+                .entry((state.to_string(), input.to_string(), output.to_string()))
+                .or_default()
+                .insert(target.to_string());
+            states.insert(state.to_string());
+            states.insert(target.to_string());
+            if *input != "&" {
+                input_alphabet.insert(input.to_string());
+            }
+            if *output != "&" {
+                output_alphabet.insert(output.to_string());
+            }
+        }
+
+        FiniteStateTransducer {
+            states,
+            input_alphabet,
+            output_alphabet,
+            transition_function,
+            start_state: start_state.to_string(),
+            accept_states: set(accept_states),
+        }
+    }
+
+    // rewrites every a into aa or c and deletes every b
+    fn make_transducer_rewrite() -> FiniteStateTransducer {
+        make_transducer(
+            "s",
+            &["s"],
+            &[
+                ("s", "a", "a", "m"),
+                ("m", "&", "a", "s"),
+                ("s", "a", "c", "s"),
+                ("s", "b", "&", "s"),
+            ],
+        )
+    }
+
+    // rewrites a into x and c into y, then appends z
+    fn make_transducer_rename() -> FiniteStateTransducer {
+        make_transducer(
+            "s",
+            &["f"],
+            &[
+                ("s", "a", "x", "s"),
+                ("s", "c", "y", "s"),
+                ("s", "&", "&", "e"),
+                ("e", "&", "z", "f"),
+            ],
+        )
+    }
+
+    #[test]
+    fn apply() {
+        let transducer = make_transducer_rewrite();
+
+        assert_eq!(transducer.apply(""), Some(set(&[""])));
+        assert_eq!(transducer.apply("ab"), Some(set(&["aa", "c"])));
+        assert_eq!(
+            transducer.apply("aba"),
+            Some(set(&["aaaa", "aac", "caa", "cc"]))
+        );
+        assert_eq!(transducer.apply("ad"), Some(BTreeSet::new()));
+
+        assert_eq!(make_transducer_rename().apply("ac"), Some(set(&["xyz"])));
+    }
+
+    #[test]
+    fn apply_infinite() {
+        let looping = make_transducer("s", &["s"], &[("s", "a", "a", "s"), ("s", "&", "b", "s")]);
+        assert_eq!(looping.apply("a"), None);
+
+        // the loop can only be entered off every accepting path
+        let dead_loop = make_transducer(
+            "s",
+            &["s"],
+            &[
+                ("s", "a", "a", "s"),
+                ("s", "a", "&", "dead"),
+                ("dead", "&", "b", "dead"),
+            ],
+        );
+        assert_eq!(dead_loop.apply("aa"), Some(set(&["aa"])));
+
+        // a loop of &:& moves writes nothing and leaves the outputs finite
+        let silent_loop = make_transducer(
+            "s",
+            &["t"],
+            &[
+                ("s", "&", "&", "t"),
+                ("t", "&", "&", "s"),
+                ("t", "a", "b", "t"),
+            ],
+        );
+        assert_eq!(silent_loop.apply("aa"), Some(set(&["bb"])));
+    }
+
+    #[test]
+    fn compose() {
+        let composed = make_transducer_rewrite().compose(&make_transducer_rename());
+
+        assert_eq!(composed.apply("ab"), Some(set(&["xxz", "yz"])));
+        assert_eq!(composed.apply("b"), Some(set(&["z"])));
+        assert_eq!(composed.input_alphabet, set(&["a", "b"]));
+        assert_eq!(composed.output_alphabet, set(&["x", "y", "z"]));
+    }
+
+    #[test]
+    fn compose_words() {
+        let expand = make_transducer("s", &["s"], &[("s", "a", "bc", "s"), ("s", "dd", "&", "s")]);
+        let rename = make_transducer("s", &["s"], &[("s", "b", "x", "s"), ("s", "c", "y", "s")]);
+
+        let composed = expand.compose(&rename);
+
+        assert_eq!(composed.apply("a"), Some(set(&["xy"])));
+        assert_eq!(composed.apply("adda"), Some(set(&["xyxy"])));
+        assert_eq!(composed.apply("ad"), Some(BTreeSet::new()));
+        assert_eq!(composed.input_alphabet, set(&["a", "d"]));
+
+        let outputs = expand.output_projection();
+        assert_eq!(outputs.alphabet, set(&["b", "c"]));
+        assert!(outputs.simulator().unwrap().compute("bcbc"));
+    }
+
+    #[test]
+    fn invert() {
+        let inverted = make_transducer_rename().invert();
+
+        assert_eq!(inverted.apply("xyz"), Some(set(&["ac"])));
+        assert_eq!(inverted.apply("xy"), Some(BTreeSet::new()));
+
+        // b is deleted, so any number of them could have been there
+        assert_eq!(make_transducer_rewrite().invert().apply("c"), None);
+    }
+
+    #[test]
+    fn projections() {
+        let transducer = make_transducer_rewrite().compose(&make_transducer_rename());
+
+        let inputs = transducer.input_projection().simulator().unwrap();
+        assert!(inputs.compute("abba"));
+        assert!(!inputs.compute("abc"));
+
+        let outputs = transducer.output_projection().simulator().unwrap();
+        assert!(outputs.compute("xxyz"));
+        assert!(!outputs.compute("xyz"));
+        assert!(!outputs.compute("xxy"));
+    }
+}
@@ -0,0 +1,317 @@
+use super::moore_machine::MooreMachine;
+use crate::regular_languages::set_to_state;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+
+// the output of a transition is written while it is taken
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MealyMachine {
+    pub states: BTreeSet<String>,
+    pub input_alphabet: BTreeSet<String>,
+    pub output_alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String), String>,
+    pub output_function: BTreeMap<(String, String), String>,
+    pub start_state: String,
+}
+
+impl fmt::Display for MealyMachine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decoration_spacing = 3;
+
+        let table_spacing = self
+            .transition_function
+            .iter()
+            .map(|(key, out_state)| {
+                out_state.len() + self.output_function.get(key).map_or(0, String::len) + 1
+            })
+            .chain(self.states.iter().map(|state| state.len()))
+            .max()
+            .unwrap_or(0)
+            + 3;
+
+        write!(
+            f,
+            "{:d$}{:width$}",
+            "",
+            "g",
+            d = decoration_spacing,
+            width = table_spacing,
+        )?;
+        for letter in &self.input_alphabet {
+            write!(f, "{:width$}", letter, width = table_spacing)?;
+        }
+
+        for state in &self.states {
+            let decorations = if *state == self.start_state { "->" } else { "" };
+            write!(
+                f,
+                "\n{:>d$}{:width$}",
+                decorations,
+                state,
+                d = decoration_spacing,
+                width = table_spacing,
+            )?;
+
+            for letter in &self.input_alphabet {
+                let key = (state.clone(), letter.clone());
+                let cell = match (
+                    self.transition_function.get(&key),
+                    self.output_function.get(&key),
+                ) {
+                    (Some(out_state), Some(output)) => out_state.clone() + "/" + output,
+                    _ => String::from("-"),
+                };
+                write!(f, "{:width$}", cell, width = table_spacing)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MealyMachine {
+    // concatenated outputs of the transitions taken, or None when one of them is missing
+    pub fn simulate(&self, input: &str) -> Option<String> {
+        let mut actual_state = self.start_state.clone();
+        let mut output = String::new();
+
+        for symbol in input.chars() {
+            let key = (actual_state, symbol.to_string());
+            output += self.output_function.get(&key)?;
+            actual_state = self.transition_function.get(&key)?.clone();
+        }
+
+        Some(output)
+    }
+
+    pub fn remove_unreachable_states(&self) -> Self {
+        let mut states = BTreeSet::new();
+        let mut stack = vec![self.start_state.clone()];
+
+        while let Some(state) = stack.pop() {
+            if !states.insert(state.clone()) {
+                continue;
+            }
+            for letter in &self.input_alphabet {
+                if let Some(out_state) = self
+                    .transition_function
+                    .get(&(state.clone(), letter.clone()))
+                {
+                    stack.push(out_state.clone());
+                }
+            }
+        }
+
+        let keep = |(state, _): &&(String, String)| states.contains(state);
+
+        MealyMachine {
+            input_alphabet: self.input_alphabet.clone(),
+            output_alphabet: self.output_alphabet.clone(),
+            transition_function: self
+                .transition_function
+                .iter()
+                .filter(|(key, _)| keep(key))
+                .map(|(key, out_state)| (key.clone(), out_state.clone()))
+                .collect(),
+            output_function: self
+                .output_function
+                .iter()
+                .filter(|(key, _)| keep(key))
+                .map(|(key, output)| (key.clone(), output.clone()))
+                .collect(),
+            start_state: self.start_state.clone(),
+            states,
+        }
+    }
+
+    // states start out split by the outputs they write for each letter, and a class is split
+    // again while its states move into different classes on some letter; the classes left
+    // become the states, named after their members
+    pub fn minimize(&self) -> Self {
+        let machine = self.remove_unreachable_states();
+
+        let row = |state: &String| -> Vec<Option<&String>> {
+            machine
+                .input_alphabet
+                .iter()
+                .map(|letter| {
+                    machine
+                        .output_function
+                        .get(&(state.clone(), letter.clone()))
+                })
+                .collect()
+        };
+
+        let mut class: BTreeMap<&String, usize> = BTreeMap::new();
+        let mut signatures = BTreeMap::new();
+        for state in &machine.states {
+            let next_class = signatures.len();
+            class.insert(state, *signatures.entry(row(state)).or_insert(next_class));
+        }
+        let mut class_count = signatures.len();
+
+        loop {
+            let mut signatures = BTreeMap::new();
+            let mut refined = BTreeMap::new();
+
+            for state in &machine.states {
+                let signature = (
+                    class[state],
+                    machine
+                        .input_alphabet
+                        .iter()
+                        .map(|letter| {
+                            machine
+                                .transition_function
+                                .get(&(state.clone(), letter.clone()))
+                                .map(|out_state| class[out_state])
+                        })
+                        .collect::<Vec<Option<usize>>>(),
+                );
+
+                let next_class = signatures.len();
+                refined.insert(state, *signatures.entry(signature).or_insert(next_class));
+            }
+
+            class = refined;
+
+            if signatures.len() == class_count {
+                break;
+            }
+            class_count = signatures.len();
+        }
+
+        let mut members: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+        for (state, state_class) in &class {
+            members
+                .entry(*state_class)
+                .or_default()
+                .insert((*state).clone());
+        }
+        let name = |state: &String| set_to_state(&members[&class[state]]);
+
+        let mut transition_function = BTreeMap::new();
+        let mut output_function = BTreeMap::new();
+
+        for ((state, letter), out_state) in &machine.transition_function {
+            transition_function.insert((name(state), letter.clone()), name(out_state));
+        }
+        for ((state, letter), output) in &machine.output_function {
+            output_function.insert((name(state), letter.clone()), output.clone());
+        }
+
+        MealyMachine {
+            states: members.values().map(set_to_state).collect(),
+            input_alphabet: machine.input_alphabet.clone(),
+            output_alphabet: machine.output_alphabet.clone(),
+            transition_function,
+            output_function,
+            start_state: name(&machine.start_state),
+        }
+    }
+}
+
+// every transition writes the output of the state it enters, and the states, alphabets and
+// transitions are kept as they are. the output of the start state is dropped, since a mealy
+// machine only writes while reading, so for every input it writes the moore output but its first
+impl From<&MooreMachine> for MealyMachine {
+    fn from(machine: &MooreMachine) -> Self {
+        MealyMachine {
+            states: machine.states.clone(),
+            input_alphabet: machine.input_alphabet.clone(),
+            output_alphabet: machine.output_alphabet.clone(),
+            transition_function: machine.transition_function.clone(),
+            output_function: machine
+                .transition_function
+                .iter()
+                .filter_map(|(key, out_state)| {
+                    machine
+                        .output_function
+                        .get(out_state)
+                        .map(|output| (key.clone(), output.clone()))
+                })
+                .collect(),
+            start_state: machine.start_state.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::set;
+
+    // writes 1 whenever the input read so far has an odd number of 1s, counted modulo 4 so
+    // that states two apart are equivalent
+    fn make_machine_parity() -> MealyMachine {
+        let mut transition_function = BTreeMap::new();
+        let mut output_function = BTreeMap::new();
+
+        for i in 0..4 {
+            let state = String::from("q") + &i.to_string();
+            let next = String::from("q") + &((i + 1) % 4).to_string();
+
+            transition_function.insert((state.clone(), String::from("0")), state.clone());
+            transition_function.insert((state.clone(), String::from("1")), next);
+            output_function.insert((state.clone(), String::from("0")), (i % 2).to_string());
+            output_function.insert((state, String::from("1")), ((i + 1) % 2).to_string());
+        }
+
+        MealyMachine {
+            states: set(&["q0", "q1", "q2", "q3", "unreachable"]),
+            input_alphabet: set(&["0", "1"]),
+            output_alphabet: set(&["0", "1"]),
+            transition_function,
+            output_function,
+            start_state: String::from("q0"),
+        }
+    }
+
+    #[test]
+    fn simulate() {
+        let machine = make_machine_parity();
+
+        assert_eq!(machine.simulate(""), Some(String::new()));
+        assert_eq!(machine.simulate("0110100"), Some(String::from("0100111")));
+        assert_eq!(machine.simulate("012"), None);
+    }
+
+    #[test]
+    fn minimize() {
+        let machine = make_machine_parity();
+        let minimized = machine.minimize();
+
+        assert_eq!(minimized.states, set(&["(q0, q2)", "(q1, q3)"]));
+        assert_eq!(minimized.start_state, "(q0, q2)");
+        assert_eq!(
+            minimized.transition_function[&(String::from("(q1, q3)"), String::from("1"))],
+            "(q0, q2)"
+        );
+
+        for input in &["", "1", "0110100", "111011101"] {
+            assert_eq!(minimized.simulate(input), machine.simulate(input));
+        }
+
+        // a missing transition keeps q0 and q2 apart, and with them q1 and q3
+        let mut partial = machine.clone();
+        partial
+            .transition_function
+            .remove(&(String::from("q2"), String::from("0")));
+        partial
+            .output_function
+            .remove(&(String::from("q2"), String::from("0")));
+        assert_eq!(partial.minimize().states.len(), 4);
+    }
+
+    #[test]
+    fn serialize() {
+        let machine = make_machine_parity().minimize();
+
+        let yaml = serde_yaml::to_string(&machine).unwrap();
+        let restored: MealyMachine = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(restored.to_string(), machine.to_string());
+    }
+}
@@ -0,0 +1,7 @@
+mod finite_state_transducer;
+mod mealy_machine;
+mod moore_machine;
+
+pub use self::finite_state_transducer::*;
+pub use self::mealy_machine::*;
+pub use self::moore_machine::*;
@@ -0,0 +1,249 @@
+use super::mealy_machine::MealyMachine;
+use crate::regular_languages::make_cartesian_state;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+
+// the output of a state is written when it is entered, the start state's before any input
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MooreMachine {
+    pub states: BTreeSet<String>,
+    pub input_alphabet: BTreeSet<String>,
+    pub output_alphabet: BTreeSet<String>,
+    pub transition_function: BTreeMap<(String, String), String>,
+    pub output_function: BTreeMap<String, String>,
+    pub start_state: String,
+}
+
+impl fmt::Display for MooreMachine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decoration_spacing = 3;
+
+        let table_spacing = self
+            .states
+            .iter()
+            .map(|state| state.len() + self.output_function.get(state).map_or(0, String::len) + 1)
+            .max()
+            .unwrap_or(0)
+            + 3;
+
+        write!(
+            f,
+            "{:d$}{:width$}",
+            "",
+            "g",
+            d = decoration_spacing,
+            width = table_spacing,
+        )?;
+        for letter in &self.input_alphabet {
+            write!(f, "{:width$}", letter, width = table_spacing)?;
+        }
+
+        for state in &self.states {
+            let decorations = if *state == self.start_state { "->" } else { "" };
+            let labeled_state = match self.output_function.get(state) {
+                Some(output) => state.clone() + "/" + output,
+                None => state.clone(),
+            };
+            write!(
+                f,
+                "\n{:>d$}{:width$}",
+                decorations,
+                labeled_state,
+                d = decoration_spacing,
+                width = table_spacing,
+            )?;
+
+            for letter in &self.input_alphabet {
+                write!(
+                    f,
+                    "{:width$}",
+                    match self
+                        .transition_function
+                        .get(&(state.clone(), letter.clone()))
+                    {
+                        Some(out_state) => out_state.clone(),
+                        None => String::from("-"),
+                    },
+                    width = table_spacing,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl MooreMachine {
+    // outputs of the start state and of every state entered, or None when a transition or an
+    // output is missing
+    pub fn simulate(&self, input: &str) -> Option<String> {
+        let mut actual_state = self.start_state.clone();
+        let mut output = self.output_function.get(&actual_state)?.clone();
+
+        for symbol in input.chars() {
+            actual_state = self
+                .transition_function
+                .get(&(actual_state, symbol.to_string()))?
+                .clone();
+            output += self.output_function.get(&actual_state)?;
+        }
+
+        Some(output)
+    }
+}
+
+// a state (q, o) is q entered while writing o, built for the pairs reachable from the start.
+// the start state (q0, "") writes the empty word, so "" joins the output alphabet even when the
+// mealy machine never writes it; in exchange both machines write the same for every input
+impl From<&MealyMachine> for MooreMachine {
+    fn from(machine: &MealyMachine) -> Self {
+        let start_state = make_cartesian_state(&machine.start_state, &String::new());
+
+        let mut states = BTreeSet::new();
+        let mut transition_function = BTreeMap::new();
+        let mut output_function = BTreeMap::new();
+
+        let mut stack = vec![(machine.start_state.clone(), String::new())];
+
+        while let Some((state, output)) = stack.pop() {
+            let pair = make_cartesian_state(&state, &output);
+            if !states.insert(pair.clone()) {
+                continue;
+            }
+            output_function.insert(pair.clone(), output);
+
+            for letter in &machine.input_alphabet {
+                let key = (state.clone(), letter.clone());
+                if let (Some(out_state), Some(out_output)) = (
+                    machine.transition_function.get(&key),
+                    machine.output_function.get(&key),
+                ) {
+                    transition_function.insert(
+                        (pair.clone(), letter.clone()),
+                        make_cartesian_state(out_state, out_output),
+                    );
+                    stack.push((out_state.clone(), out_output.clone()));
+                }
+            }
+        }
+
+        let mut output_alphabet = machine.output_alphabet.clone();
+        output_alphabet.insert(String::new());
+
+        MooreMachine {
+            states,
+            input_alphabet: machine.input_alphabet.clone(),
+            output_alphabet,
+            transition_function,
+            output_function,
+            start_state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::regular_languages::fixtures::{set, words};
+
+    // writes the remainder modulo 3 of the binary number read so far
+    fn make_machine_remainder() -> MooreMachine {
+        let mut transition_function = BTreeMap::new();
+        let mut output_function = BTreeMap::new();
+
+        for remainder in 0..3 {
+            let state = String::from("r") + &remainder.to_string();
+            for digit in 0..2 {
+                transition_function.insert(
+                    (state.clone(), digit.to_string()),
+                    String::from("r") + &((remainder * 2 + digit) % 3).to_string(),
+                );
+            }
+            output_function.insert(state, remainder.to_string());
+        }
+
+        MooreMachine {
+            states: set(&["r0", "r1", "r2"]),
+            input_alphabet: set(&["0", "1"]),
+            output_alphabet: set(&["0", "1", "2"]),
+            transition_function,
+            output_function,
+            start_state: String::from("r0"),
+        }
+    }
+
+    #[test]
+    fn simulate() {
+        let machine = make_machine_remainder();
+
+        assert_eq!(machine.simulate(""), Some(String::from("0")));
+        assert_eq!(machine.simulate("1011"), Some(String::from("01222")));
+        assert_eq!(machine.simulate("10a"), None);
+    }
+
+    #[test]
+    fn conversions() {
+        let moore = make_machine_remainder();
+        let mealy = MealyMachine::from(&moore);
+
+        for input in &["", "1", "1011", "110100111"] {
+            assert_eq!(
+                mealy.simulate(input).unwrap(),
+                moore.simulate(input).unwrap()[1..]
+            );
+        }
+
+        assert_eq!(
+            MealyMachine::from(&MooreMachine::from(&mealy))
+                .minimize()
+                .states
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let moore = make_machine_remainder();
+        let round_trip = MooreMachine::from(&MealyMachine::from(&moore));
+
+        // the output of the start state is lost for good: it becomes the empty word, which joins
+        // the output alphabet, and the start state is split from r0 entered later
+        assert_eq!(round_trip.start_state, "(r0, )");
+        assert_eq!(round_trip.output_function[&round_trip.start_state], "");
+        assert_eq!(round_trip.output_alphabet, set(&["", "0", "1", "2"]));
+        assert_eq!(
+            round_trip.states,
+            set(&["(r0, )", "(r0, 0)", "(r1, 1)", "(r2, 2)"])
+        );
+        for input in words(&["0", "1"], 6) {
+            assert_eq!(
+                round_trip.simulate(&input).unwrap(),
+                moore.simulate(&input).unwrap()[1..]
+            );
+        }
+
+        // the other way around the outputs are all kept, only the states and the extra empty
+        // output differ
+        let mealy = MealyMachine::from(&moore);
+        let round_trip = MealyMachine::from(&MooreMachine::from(&mealy));
+
+        assert_eq!(round_trip.output_alphabet, set(&["", "0", "1", "2"]));
+        assert_eq!(round_trip.states.len(), 4);
+        for input in words(&["0", "1"], 6) {
+            assert_eq!(round_trip.simulate(&input), mealy.simulate(&input));
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let machine = make_machine_remainder();
+
+        let yaml = serde_yaml::to_string(&machine).unwrap();
+        let restored: MooreMachine = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(restored.to_string(), machine.to_string());
+    }
+}